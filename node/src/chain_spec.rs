@@ -1,18 +1,100 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature, ContractsConfig
+	SudoConfig, SystemConfig, WASM_BINARY, Signature, ContractsConfig, PoeModuleConfig,
+	IndicesConfig, SessionConfig, ValidatorSetConfig, CouncilConfig, CouncilMembershipConfig,
+	DemocracyConfig, VestingConfig, Balance, BlockNumber, CENTS, DAYS, opaque::SessionKeys,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
 use sc_service::ChainType;
+use sc_chain_spec::ChainSpecExtension;
+use serde::{Serialize, Deserialize};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Node-side settings carried in the chain spec alongside the genesis.
+#[derive(Default, Clone, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+	/// GRANDPA finality settings.
+	#[serde(default)]
+	pub grandpa: GrandpaSettings,
+}
+
+/// GRANDPA finality settings, overridable from the command line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrandpaSettings {
+	/// Interval between gossip rounds, in milliseconds.
+	pub gossip_duration_ms: u64,
+	/// Number of blocks between justifications stored for finalized blocks.
+	pub justification_period: u32,
+	/// Whether to enable the GRANDPA observer protocol.
+	pub observer_enabled: bool,
+}
+
+impl Default for GrandpaSettings {
+	fn default() -> Self {
+		Self {
+			gossip_duration_ms: 333,
+			justification_period: 512,
+			observer_enabled: false,
+		}
+	}
+}
+
+impl GrandpaSettings {
+	/// Longest accepted gossip interval; beyond this, finality would stall for too long
+	/// between rounds.
+	pub const MAX_GOSSIP_DURATION_MS: u64 = 10_000;
+
+	/// Checks that the settings can run a GRANDPA voter.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.gossip_duration_ms == 0 || self.gossip_duration_ms > Self::MAX_GOSSIP_DURATION_MS {
+			return Err(format!(
+				"GRANDPA gossip duration must be between 1 and {} ms, got {}",
+				Self::MAX_GOSSIP_DURATION_MS,
+				self.gossip_duration_ms,
+			));
+		}
+
+		if self.justification_period == 0 {
+			return Err("GRANDPA justification period must be at least 1 block".into());
+		}
+
+		Ok(())
+	}
+}
+
+/// Balance given to each pre-funded account.
+const ENDOWMENT: Balance = 1 << 60;
+
+/// A genesis balance that unlocks linearly, e.g. a contributor's share of the token
+/// distribution.
+#[derive(Debug, Clone)]
+pub struct VestingSchedule {
+	/// Account receiving the balance. An account can have at most one schedule.
+	pub account: AccountId,
+	/// Block from which the balance starts to unlock.
+	pub start: BlockNumber,
+	/// Number of blocks over which the balance unlocks.
+	pub period: BlockNumber,
+	/// Balance unlocked each block.
+	pub per_block: Balance,
+}
+
+impl VestingSchedule {
+	/// The total balance locked at `start`.
+	pub fn locked(&self) -> Balance {
+		self.per_block.saturating_mul(self.period.into())
+	}
+}
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -30,14 +112,19 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+/// Generate the validator account and its Aura and Grandpa session keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
 	(
+		get_account_id_from_seed::<sr25519::Public>(s),
 		get_from_seed::<AuraId>(s),
 		get_from_seed::<GrandpaId>(s),
 	)
 }
 
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { aura, grandpa }
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
 
@@ -62,6 +149,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			// Vesting schedules
+			vec![],
 			true,
 		),
 		// Bootnodes
@@ -73,7 +162,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Properties
 		None,
 		// Extensions
-		None,
+		Extensions::default(),
 	))
 }
 
@@ -110,6 +199,15 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			// Vesting schedules
+			vec![
+				VestingSchedule {
+					account: get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+					start: 0,
+					period: 30 * DAYS,
+					per_block: 1 * CENTS,
+				},
+			],
 			true,
 		),
 		// Bootnodes
@@ -121,18 +219,38 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Properties
 		None,
 		// Extensions
-		None,
+		Extensions::default(),
 	))
 }
 
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	vesting: Vec<VestingSchedule>,
 	enable_println: bool,
 ) -> GenesisConfig {
+	// Vested balances come on top of the endowment, which stays liquid.
+	let mut balances: Vec<(AccountId, Balance)> = endowed_accounts.into_iter()
+		.map(|k| (k, ENDOWMENT))
+		.collect();
+	for (i, schedule) in vesting.iter().enumerate() {
+		// `pallet_vesting` keeps one schedule per account, so a second one would silently make
+		// the first one's amount liquid.
+		assert!(
+			vesting[..i].iter().all(|other| other.account != schedule.account),
+			"Account {} has more than one vesting schedule",
+			schedule.account,
+		);
+		match balances.iter_mut().find(|(k, _)| k == &schedule.account) {
+			Some((_, balance)) => *balance = balance.checked_add(schedule.locked())
+				.unwrap_or_else(|| panic!("Genesis balance of {} overflows", schedule.account)),
+			None => balances.push((schedule.account.clone(), schedule.locked())),
+		}
+	}
+
 	GenesisConfig {
 		frame_system: Some(SystemConfig {
 			// Add Wasm runtime to storage.
@@ -140,14 +258,24 @@ fn testnet_genesis(
 			changes_trie_config: Default::default(),
 		}),
 		pallet_balances: Some(BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			balances: balances.clone(),
 		}),
+		pallet_vesting: Some(VestingConfig {
+			// `pallet_vesting` takes the liquid part of the balance and derives the amount
+			// unlocked per block from the rest.
+			vesting: vesting.iter().map(|schedule| {
+				let balance = balances.iter()
+					.find(|(k, _)| k == &schedule.account)
+					.map_or(0, |(_, balance)| *balance);
+				(schedule.account.clone(), schedule.start, schedule.period, balance - schedule.locked())
+			}).collect(),
+		}),
+		// Aura and Grandpa authorities are set up by `pallet_session` from the session keys.
 		pallet_aura: Some(AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+			authorities: vec![],
 		}),
 		pallet_grandpa: Some(GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+			authorities: vec![],
 		}),
 		pallet_sudo: Some(SudoConfig {
 			// Assign network admin rights.
@@ -160,5 +288,26 @@ fn testnet_genesis(
                     ..Default::default()
             },
         }),
+
+		pallet_poe: Some(PoeModuleConfig::default()),
+		pallet_indices: Some(IndicesConfig {
+			indices: vec![],
+		}),
+		pallet_validator_set: Some(ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+		}),
+		pallet_treasury: Some(Default::default()),
+		// The council is populated from `pallet_membership`.
+		pallet_collective_Instance1: Some(CouncilConfig::default()),
+		pallet_membership_Instance1: Some(CouncilMembershipConfig {
+			members: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+			phantom: Default::default(),
+		}),
+		pallet_democracy: Some(DemocracyConfig::default()),
+		pallet_session: Some(SessionConfig {
+			keys: initial_authorities.iter().map(|x| {
+				(x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone()))
+			}).collect(),
+		}),
 	}
 }
@@ -1,5 +1,8 @@
 use structopt::StructOpt;
 use sc_cli::RunCmd;
+use sc_service::ChainSpec;
+use crate::chain_spec::GrandpaSettings;
+use crate::service::Sealing;
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -8,6 +11,71 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	#[structopt(flatten)]
+	pub grandpa: GrandpaParams,
+
+	/// Seal a block as soon as a transaction enters the pool, instead of running Aura and GRANDPA.
+	#[structopt(long, conflicts_with = "manual-seal")]
+	pub instant_seal: bool,
+
+	/// Only seal blocks when asked to through the `engine_createBlock` RPC, instead of running
+	/// Aura and GRANDPA.
+	#[structopt(long)]
+	pub manual_seal: bool,
+}
+
+impl Cli {
+	/// The sealing mode selected on the command line, if any.
+	pub fn sealing(&self) -> Option<Sealing> {
+		if self.instant_seal {
+			Some(Sealing::Instant)
+		} else if self.manual_seal {
+			Some(Sealing::Manual)
+		} else {
+			None
+		}
+	}
+}
+
+/// Overrides for the GRANDPA settings in the chain spec.
+#[derive(Debug, StructOpt)]
+pub struct GrandpaParams {
+	/// Interval between GRANDPA gossip rounds, in milliseconds.
+	#[structopt(long = "grandpa-gossip-duration", value_name = "MS")]
+	pub gossip_duration: Option<u64>,
+
+	/// Number of blocks between justifications stored for finalized blocks.
+	#[structopt(long = "grandpa-justification-period", value_name = "BLOCKS")]
+	pub justification_period: Option<u32>,
+
+	/// Whether to enable the GRANDPA observer protocol.
+	#[structopt(long = "grandpa-observer-enabled", value_name = "BOOL")]
+	pub observer_enabled: Option<bool>,
+}
+
+impl GrandpaParams {
+	/// The chain spec's GRANDPA settings with these overrides applied.
+	///
+	/// Fails if the resulting settings are invalid.
+	pub fn settings(&self, chain_spec: &dyn ChainSpec) -> Result<GrandpaSettings, String> {
+		let mut settings = sc_chain_spec::get_extension::<GrandpaSettings>(chain_spec.extensions())
+			.cloned()
+			.unwrap_or_default();
+
+		if let Some(gossip_duration) = self.gossip_duration {
+			settings.gossip_duration_ms = gossip_duration;
+		}
+		if let Some(justification_period) = self.justification_period {
+			settings.justification_period = justification_period;
+		}
+		if let Some(observer_enabled) = self.observer_enabled {
+			settings.observer_enabled = observer_enabled;
+		}
+
+		settings.validate()?;
+		Ok(settings)
+	}
 }
 
 #[derive(Debug, StructOpt)]
@@ -33,6 +101,12 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Notarise and verify files with the proof of existence pallet.
+	Poe(crate::poe::PoeCmd),
+
+	/// Inspect smart contracts deployed on a running node.
+	Contracts(crate::contracts::ContractsCmd),
+
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
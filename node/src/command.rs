@@ -114,6 +114,8 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, backend), task_manager))
 			})
 		},
+		Some(Subcommand::Poe(cmd)) => cmd.run(),
+		Some(Subcommand::Contracts(cmd)) => cmd.run(),
 		Some(Subcommand::Benchmark(cmd)) => {
 			if cfg!(feature = "runtime-benchmarks") {
 				let runner = cli.create_runner(cmd)?;
@@ -126,9 +128,14 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| match config.role {
-				Role::Light => service::new_light(config),
-				_ => service::new_full(config),
+			let sealing = cli.sealing();
+			runner.run_node_until_exit(|config| match (config.role.clone(), sealing) {
+				(Role::Light, _) => service::new_light(config),
+				(_, Some(sealing)) => service::new_manual_seal(config, sealing),
+				(_, None) => {
+					let grandpa = cli.grandpa.settings(&*config.chain_spec)?;
+					service::new_full(config, grandpa)
+				},
 			})
 		}
 	}
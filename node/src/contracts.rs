@@ -0,0 +1,120 @@
+//! `contracts` subcommands for inspecting smart contracts on a running node.
+
+use codec::Decode;
+use frame_support::storage::StoragePrefixedMap;
+use futures::{compat::Future01CompatExt, TryFutureExt};
+use node_template_runtime::{AccountId, Balance, BlockNumber, Hash, Header, Runtime, SignedBlock};
+use pallet_contracts::{ContractInfo, ContractInfoOf};
+use pallet_contracts_rpc::gen_client::Client as ContractsClient;
+use sc_rpc_api::{chain::ChainClient, state::StateClient};
+use sp_core::storage::StorageKey;
+use sp_runtime::traits::Header as HeaderT;
+use structopt::StructOpt;
+
+use crate::poe::{block_on, connect, input_error, NodeParams};
+
+#[derive(Debug, StructOpt)]
+pub enum ContractsCmd {
+	/// Report when each contract will be evicted for not paying rent.
+	RentReport(RentReportCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct RentReportCmd {
+	#[structopt(flatten)]
+	pub node: NodeParams,
+
+	/// Only report live contracts that can be evicted within this many blocks.
+	#[structopt(long, value_name = "BLOCKS")]
+	pub within: Option<BlockNumber>,
+}
+
+impl ContractsCmd {
+	pub fn run(&self) -> sc_cli::Result<()> {
+		match self {
+			ContractsCmd::RentReport(cmd) => block_on(cmd.run()),
+		}
+	}
+}
+
+/// What will become of a contract, as far as rent is concerned.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Projection {
+	/// Already evicted.
+	Tombstone,
+	/// Will run out of rent at the given block; it can be evicted as soon as that block.
+	EvictionAt(BlockNumber),
+	/// Pays no rent, or has enough balance to pay it forever.
+	NoEviction,
+}
+
+impl RentReportCmd {
+	async fn run(&self) -> sc_cli::Result<()> {
+		let channel = connect(&self.node.url).await?;
+		let chain = ChainClient::<BlockNumber, Hash, Header, SignedBlock>::from(channel.clone());
+		let state = StateClient::<Hash>::from(channel.clone());
+		let contracts = ContractsClient::<Hash, BlockNumber, AccountId, Balance>::from(channel);
+
+		// Pin all queries to the same block.
+		let best = chain.header(None).compat().await.map_err(input_error)?
+			.ok_or_else(|| input_error("node did not return its best block"))?;
+		let at = best.hash();
+
+		let prefix = StorageKey(ContractInfoOf::<Runtime>::final_prefix().to_vec());
+		let keys = state.storage_keys(prefix, Some(at)).compat().await.map_err(input_error)?;
+
+		let mut report = Vec::with_capacity(keys.len());
+		for key in keys {
+			// `twox_64_concat` keys end with the account id, after the prefix and an 8-byte hash.
+			let address = AccountId::decode(&mut &key.0[32 + 8..])
+				.map_err(|e| input_error(format!("failed to decode contract address: {}", e)))?;
+
+			let info = state.storage(key, Some(at)).compat().await.map_err(input_error)?
+				.map(|data| ContractInfo::<Runtime>::decode(&mut &data.0[..]))
+				.transpose()
+				.map_err(|e| input_error(format!("failed to decode contract info: {}", e)))?;
+
+			let projection = match info {
+				Some(ContractInfo::Alive(_)) => {
+					let eviction = contracts.rent_projection(address.clone(), Some(at))
+						.compat().await.map_err(input_error)?;
+					eviction.map_or(Projection::NoEviction, Projection::EvictionAt)
+				},
+				Some(ContractInfo::Tombstone(_)) => Projection::Tombstone,
+				None => continue,
+			};
+
+			report.push((projection, address));
+		}
+
+		if let Some(within) = self.within {
+			let horizon = best.number.saturating_add(within);
+			report.retain(|(projection, _)| match projection {
+				Projection::EvictionAt(block) => *block <= horizon,
+				_ => false,
+			});
+		}
+
+		// Tombstones first, then live contracts by how soon they run out of rent.
+		report.sort();
+
+		let mut evictable = 0;
+		for (projection, address) in &report {
+			match projection {
+				Projection::Tombstone => println!("{} tombstone", address),
+				Projection::EvictionAt(block) if *block <= best.number => {
+					evictable += 1;
+					println!("{} evictable since block {}", address, block);
+				},
+				Projection::EvictionAt(block) => println!("{} evicted at block {}", address, block),
+				Projection::NoEviction => println!("{} never evicted", address),
+			}
+		}
+
+		println!(
+			"{} contracts at block {}, {} of which can be evicted now with `contracts.claimSurcharge`",
+			report.len(), best.number, evictable,
+		);
+		Ok(())
+	}
+}
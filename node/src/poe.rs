@@ -0,0 +1,224 @@
+//! `poe` subcommands for notarising and verifying files against a running node.
+//!
+//! These talk to a node over its WebSocket RPC, so they work against any chain running
+//! this runtime, not just a local database.
+
+use std::{fmt::Display, fs, path::PathBuf};
+
+use codec::{Decode, Encode};
+use frame_support::StorageMap;
+use futures::{compat::Future01CompatExt, TryFutureExt};
+use jsonrpc_core_client::{transports::ws, RpcChannel};
+use node_template_runtime::{
+	AccountId, BlockNumber, Call, Hash, Header, Index, Runtime, SignedBlock, SignedExtra,
+	UncheckedExtrinsic,
+};
+use pallet_poe::{ClaimOf, DocumentHash, HashAlgorithm};
+use sc_rpc_api::{author::AuthorClient, chain::ChainClient, state::StateClient};
+use sp_core::{hexdisplay::HexDisplay, sr25519, storage::StorageKey, Pair};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::{
+	generic::{Era, SignedPayload},
+	traits::IdentifyAccount,
+	MultiSigner,
+};
+use structopt::StructOpt;
+use substrate_frame_rpc_system::gen_client::Client as SystemClient;
+
+#[derive(Debug, StructOpt)]
+pub enum PoeCmd {
+	/// Print the digest of a file.
+	Hash(HashCmd),
+
+	/// Sign and submit a claim on the digest of a file.
+	///
+	/// By default the raw digest bytes are claimed with `create_claim`. With `--typed`, the
+	/// digest is claimed together with its algorithm with `create_claim_for_hash`, so that the
+	/// same bytes produced by different hash functions are told apart.
+	Claim(ClaimCmd),
+
+	/// Look up the claim on the digest of a file, made with or without `--typed`.
+	Verify(VerifyCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct DocumentParams {
+	/// The file to notarise.
+	#[structopt(parse(from_os_str))]
+	pub file: PathBuf,
+
+	/// Hash function used to digest the file: blake2-256, sha2-256 or keccak-256.
+	#[structopt(long, default_value = "blake2-256")]
+	pub algorithm: HashAlgorithm,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ClaimKindParams {
+	/// Claim the digest with its algorithm (`create_claim_for_hash`) instead of the raw digest
+	/// bytes (`create_claim`).
+	#[structopt(long)]
+	pub typed: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct NodeParams {
+	/// WebSocket RPC endpoint of the node to talk to.
+	#[structopt(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct HashCmd {
+	#[structopt(flatten)]
+	pub document: DocumentParams,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ClaimCmd {
+	#[structopt(flatten)]
+	pub document: DocumentParams,
+
+	#[structopt(flatten)]
+	pub kind: ClaimKindParams,
+
+	#[structopt(flatten)]
+	pub node: NodeParams,
+
+	/// Secret URI of the claiming account, e.g. `//Alice`.
+	#[structopt(long)]
+	pub suri: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct VerifyCmd {
+	#[structopt(flatten)]
+	pub document: DocumentParams,
+
+	#[structopt(flatten)]
+	pub kind: ClaimKindParams,
+
+	#[structopt(flatten)]
+	pub node: NodeParams,
+}
+
+impl PoeCmd {
+	pub fn run(&self) -> sc_cli::Result<()> {
+		match self {
+			PoeCmd::Hash(cmd) => {
+				let hash = cmd.document.hash()?;
+				println!("{} 0x{}", hash.algorithm, HexDisplay::from(&hash.digest));
+				Ok(())
+			},
+			PoeCmd::Claim(cmd) => block_on(cmd.run()),
+			PoeCmd::Verify(cmd) => block_on(cmd.run()),
+		}
+	}
+}
+
+impl DocumentParams {
+	fn hash(&self) -> sc_cli::Result<DocumentHash> {
+		let data = fs::read(&self.file)?;
+		Ok(DocumentHash::of(self.algorithm, &data))
+	}
+}
+
+impl ClaimCmd {
+	async fn run(&self) -> sc_cli::Result<()> {
+		let hash = self.document.hash()?;
+		let pair = sr25519::Pair::from_string(&self.suri, None)
+			.map_err(|e| input_error(format!("invalid --suri: {:?}", e)))?;
+		let signer: AccountId = MultiSigner::from(pair.public()).into_account();
+
+		let channel = connect(&self.node.url).await?;
+		let author = AuthorClient::<Hash, Hash>::from(channel.clone());
+		let chain = ChainClient::<BlockNumber, Hash, Header, SignedBlock>::from(channel.clone());
+		let state = StateClient::<Hash>::from(channel.clone());
+		let system = SystemClient::<AccountId, Index>::from(channel);
+
+		let genesis_hash = match chain.block_hash(Some(ListOrValue::Value(NumberOrHex::Number(0))))
+			.compat().await.map_err(input_error)?
+		{
+			ListOrValue::Value(Some(hash)) => hash,
+			_ => return Err(input_error("node did not return a genesis hash")),
+		};
+		let version = state.runtime_version(None).compat().await.map_err(input_error)?;
+		let nonce = system.nonce(signer.clone()).compat().await.map_err(input_error)?;
+
+		let call = if self.kind.typed {
+			Call::PoeModule(pallet_poe::Call::create_claim_for_hash(hash.algorithm, hash.digest))
+		} else {
+			Call::PoeModule(pallet_poe::Call::create_claim(hash.digest.to_vec()))
+		};
+		let extra: SignedExtra = (
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(Era::Immortal),
+			frame_system::CheckNonce::from(nonce),
+			frame_system::CheckWeight::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(0),
+		);
+		let payload = SignedPayload::from_raw(
+			call,
+			extra,
+			(version.spec_version, version.transaction_version, genesis_hash, genesis_hash, (), (), ()),
+		);
+		let signature = payload.using_encoded(|payload| pair.sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+		let extrinsic = UncheckedExtrinsic::new_signed(call, signer.into(), signature.into(), extra);
+
+		let tx_hash = author.submit_extrinsic(extrinsic.encode().into())
+			.compat().await.map_err(input_error)?;
+
+		println!("submitted claim on {} 0x{} in {:?}", hash.algorithm, HexDisplay::from(&hash.digest), tx_hash);
+		Ok(())
+	}
+}
+
+impl VerifyCmd {
+	async fn run(&self) -> sc_cli::Result<()> {
+		let hash = self.document.hash()?;
+
+		let channel = connect(&self.node.url).await?;
+		let state = StateClient::<Hash>::from(channel);
+
+		let key = if self.kind.typed {
+			StorageKey(pallet_poe::HashedProofs::<Runtime>::hashed_key_for(&hash))
+		} else {
+			StorageKey(pallet_poe::Proofs::<Runtime>::hashed_key_for(&hash.digest.to_vec()))
+		};
+		let claim = state.storage(key, None).compat().await.map_err(input_error)?
+			.map(|data| ClaimOf::<Runtime>::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|e| input_error(format!("failed to decode claim: {}", e)))?;
+
+		match claim {
+			Some(claim) => {
+				println!("claimed by {} at block {}", claim.owner, claim.block);
+				Ok(())
+			},
+			None => Err(input_error(format!(
+				"no claim on {} 0x{}", hash.algorithm, HexDisplay::from(&hash.digest),
+			))),
+		}
+	}
+}
+
+pub(crate) async fn connect(url: &str) -> sc_cli::Result<RpcChannel> {
+	let url = url.parse().map_err(|e| input_error(format!("invalid --url: {}", e)))?;
+	ws::connect::<RpcChannel>(&url).compat().await
+		.map_err(|e| input_error(format!("failed to connect to {}: {}", url, e)))
+}
+
+/// Runs `future` to completion on a tokio 0.1 runtime, which the WebSocket transport requires.
+pub(crate) fn block_on<F>(future: F) -> sc_cli::Result<()>
+where
+	F: std::future::Future<Output = sc_cli::Result<()>>,
+{
+	let mut runtime = tokio01::runtime::current_thread::Runtime::new()?;
+	runtime.block_on(Box::pin(future).compat())
+}
+
+pub(crate) fn input_error(e: impl Display) -> sc_cli::Error {
+	sc_cli::Error::Input(e.to_string())
+}
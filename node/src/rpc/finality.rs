@@ -0,0 +1,59 @@
+//! RPC proving finality of blocks to light clients and bridges.
+
+use std::sync::Arc;
+
+use codec::Encode;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sc_network::config::FinalityProofProvider;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+/// GRANDPA finality proof RPC methods.
+#[rpc]
+pub trait FinalityApi<Hash> {
+	/// Proves finality of `block` to a client that last saw `last_finalized` finalized by the
+	/// authority set `authorities_set_id`.
+	///
+	/// Returns the SCALE-encoded GRANDPA finality proof, or `None` if `block` is not finalized yet.
+	#[rpc(name = "grandpa_proveFinality")]
+	fn prove_finality(
+		&self,
+		block: Hash,
+		last_finalized: Hash,
+		authorities_set_id: u64,
+	) -> Result<Option<Bytes>>;
+}
+
+/// Implements `FinalityApi` with the finality proof provider also serving the network.
+pub struct Finality<Block: BlockT> {
+	provider: Arc<dyn FinalityProofProvider<Block>>,
+}
+
+impl<Block: BlockT> Finality<Block> {
+	/// Creates a new instance of the finality RPC.
+	pub fn new(provider: Arc<dyn FinalityProofProvider<Block>>) -> Self {
+		Self { provider }
+	}
+}
+
+impl<Block: BlockT> FinalityApi<Block::Hash> for Finality<Block> {
+	fn prove_finality(
+		&self,
+		block: Block::Hash,
+		last_finalized: Block::Hash,
+		authorities_set_id: u64,
+	) -> Result<Option<Bytes>> {
+		// Same encoding as `FinalityProofRequest::Original` in `sc_finality_grandpa`, the request
+		// format of the network finality proof protocol.
+		let request = (0u8, authorities_set_id, last_finalized).encode();
+
+		self.provider.prove_finality(block, &request)
+			.map(|proof| proof.map(Bytes))
+			.map_err(|e| Error {
+				code: ErrorCode::ServerError(1),
+				message: "Unable to prove finality.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})
+	}
+}
@@ -0,0 +1,252 @@
+//! Proof of existence RPC for full and light clients.
+//!
+//! Full nodes read claims from their own storage and check Merkle proofs through the runtime.
+//! Light clients read both from remote storage proofs fetched on demand, so they do not need
+//! to call into the runtime, which a light client cannot do.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use frame_support::StorageMap;
+use futures::{FutureExt, TryFutureExt};
+use jsonrpc_core::{BoxFuture, Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use node_template_runtime::{opaque::Block, AccountId, BlockNumber, Hash, Runtime};
+use pallet_poe::{ClaimOf, DocumentHash, HashAlgorithm, MerkleClaimOf};
+use pallet_poe_runtime_api::PoeApi as PoeRuntimeApi;
+use sc_client_api::{
+	light::{future_header, Fetcher, RemoteBlockchain, RemoteReadRequest},
+	StorageProvider,
+};
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, Bytes, H256};
+use sp_runtime::generic::BlockId;
+
+/// A claim on a document hash, as returned over RPC.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimInfo {
+	/// Account owning the claim.
+	pub owner: AccountId,
+	/// Block at which the claim was created.
+	pub block: BlockNumber,
+	/// Free-form description of the document.
+	pub description: Option<Bytes>,
+	/// MIME type of the document.
+	pub mime_type: Option<Bytes>,
+	/// Block at which the claim is removed.
+	pub expires_at: Option<BlockNumber>,
+}
+
+impl From<ClaimOf<Runtime>> for ClaimInfo {
+	fn from(claim: ClaimOf<Runtime>) -> Self {
+		ClaimInfo {
+			owner: claim.owner,
+			block: claim.block,
+			description: claim.metadata.description.map(Bytes),
+			mime_type: claim.metadata.mime_type.map(Bytes),
+			expires_at: claim.metadata.expires_at,
+		}
+	}
+}
+
+/// Proof of existence RPC methods.
+#[rpc]
+pub trait PoeApi<BlockHash> {
+	/// Returns the claim on `digest` made with `algorithm` (`blake2-256` by default), at block
+	/// `at` or the best block.
+	#[rpc(name = "poe_claimOf")]
+	fn claim_of(
+		&self,
+		digest: H256,
+		algorithm: Option<String>,
+		at: Option<BlockHash>,
+	) -> BoxFuture<Option<ClaimInfo>>;
+
+	/// Checks that `leaf` is the `index`-th leaf of the claimed Merkle `root`, at block `at` or
+	/// the best block.
+	#[rpc(name = "poe_verifyMerkleLeaf")]
+	fn verify_merkle_leaf(
+		&self,
+		root: H256,
+		leaf: H256,
+		index: u32,
+		proof: Vec<H256>,
+		at: Option<BlockHash>,
+	) -> BoxFuture<bool>;
+}
+
+/// Implements `PoeApi` for full nodes.
+pub struct FullPoe<C, B> {
+	client: Arc<C>,
+	_backend: PhantomData<B>,
+}
+
+impl<C, B> FullPoe<C, B> {
+	/// Creates a new instance of the full PoE RPC.
+	pub fn new(client: Arc<C>) -> Self {
+		FullPoe { client, _backend: PhantomData }
+	}
+}
+
+impl<C, B> PoeApi<Hash> for FullPoe<C, B> where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	C: Send + Sync + 'static,
+	C::Api: PoeRuntimeApi<Block>,
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+	fn claim_of(
+		&self,
+		digest: H256,
+		algorithm: Option<String>,
+		at: Option<Hash>,
+	) -> BoxFuture<Option<ClaimInfo>> {
+		let claim = document_hash(digest, algorithm).and_then(|hash| {
+			let at = BlockId::Hash(at.unwrap_or_else(|| self.client.info().best_hash));
+			let key = StorageKey(pallet_poe::HashedProofs::<Runtime>::hashed_key_for(&hash));
+
+			self.client.storage(&at, &key)
+				.map_err(|e| error("Unable to read storage.", e))?
+				.map(|data| ClaimOf::<Runtime>::decode(&mut &data.0[..]))
+				.transpose()
+				.map(|claim| claim.map(ClaimInfo::from))
+				.map_err(|e| error("Unable to decode storage value.", e))
+		});
+
+		Box::new(jsonrpc_core::futures::future::result(claim))
+	}
+
+	fn verify_merkle_leaf(
+		&self,
+		root: H256,
+		leaf: H256,
+		index: u32,
+		proof: Vec<H256>,
+		at: Option<Hash>,
+	) -> BoxFuture<bool> {
+		let at = BlockId::Hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let proof = proof.into_iter().map(Into::into).collect::<Vec<[u8; 32]>>();
+
+		let verified = self.client.runtime_api()
+			.verify_merkle_leaf(&at, root.into(), leaf.into(), index, proof)
+			.map_err(|e| error("Unable to verify the Merkle proof.", e));
+
+		Box::new(jsonrpc_core::futures::future::result(verified))
+	}
+}
+
+/// Implements `PoeApi` for light clients.
+pub struct LightPoe<C, F> {
+	client: Arc<C>,
+	remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+	fetcher: Arc<F>,
+}
+
+impl<C, F> LightPoe<C, F> {
+	/// Creates a new instance of the light PoE RPC.
+	pub fn new(
+		client: Arc<C>,
+		remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+		fetcher: Arc<F>,
+	) -> Self {
+		LightPoe { client, remote_blockchain, fetcher }
+	}
+}
+
+impl<C, F> LightPoe<C, F> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+{
+	/// Fetches and decodes the value at storage `key` at block `at` from a full node.
+	fn remote_read<T: Decode + Send + 'static>(
+		&self,
+		key: Vec<u8>,
+		at: Option<Hash>,
+	) -> impl std::future::Future<Output = Result<Option<T>>> + Send {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let header = future_header(&*self.remote_blockchain, &*self.fetcher, BlockId::Hash(at));
+		let fetcher = self.fetcher.clone();
+
+		async move {
+			let header = header.await
+				.map_err(|e| error("Unable to fetch header.", e))?
+				.ok_or_else(|| error("Unknown block.", at))?;
+
+			let mut values = fetcher.remote_read(RemoteReadRequest {
+				block: at,
+				header,
+				keys: vec![key.clone()],
+				retry_count: None,
+			}).await.map_err(|e| error("Unable to read remote storage.", e))?;
+
+			values.remove(&key)
+				.flatten()
+				.map(|data| T::decode(&mut &data[..]))
+				.transpose()
+				.map_err(|e| error("Unable to decode storage value.", e))
+		}
+	}
+}
+
+impl<C, F> PoeApi<Hash> for LightPoe<C, F> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+{
+	fn claim_of(
+		&self,
+		digest: H256,
+		algorithm: Option<String>,
+		at: Option<Hash>,
+	) -> BoxFuture<Option<ClaimInfo>> {
+		let hash = match document_hash(digest, algorithm) {
+			Ok(hash) => hash,
+			Err(e) => return Box::new(jsonrpc_core::futures::future::err(e)),
+		};
+		let key = pallet_poe::HashedProofs::<Runtime>::hashed_key_for(&hash);
+
+		let claim = self.remote_read::<ClaimOf<Runtime>>(key, at)
+			.map_ok(|claim| claim.map(ClaimInfo::from));
+
+		Box::new(claim.boxed().compat())
+	}
+
+	fn verify_merkle_leaf(
+		&self,
+		root: H256,
+		leaf: H256,
+		index: u32,
+		proof: Vec<H256>,
+		at: Option<Hash>,
+	) -> BoxFuture<bool> {
+		let root: [u8; 32] = root.into();
+		let key = pallet_poe::MerkleRoots::<Runtime>::hashed_key_for(&root);
+
+		let verified = self.remote_read::<MerkleClaimOf<Runtime>>(key, at)
+			.map_ok(move |claim| match claim {
+				Some(claim) => {
+					let proof = proof.into_iter().map(Into::into).collect::<Vec<[u8; 32]>>();
+					pallet_poe::merkle::verify_proof(&root, &leaf.into(), index, claim.leaf_count, &proof)
+				},
+				None => false,
+			});
+
+		Box::new(verified.boxed().compat())
+	}
+}
+
+/// The document hash of `digest` made with `algorithm`, `blake2-256` by default.
+fn document_hash(digest: H256, algorithm: Option<String>) -> Result<DocumentHash> {
+	let algorithm = algorithm.as_deref().unwrap_or("blake2-256").parse::<HashAlgorithm>()
+		.map_err(Error::invalid_params)?;
+	Ok(DocumentHash { algorithm, digest: digest.into() })
+}
+
+fn error(message: &str, e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(1),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
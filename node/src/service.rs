@@ -1,15 +1,24 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use std::sync::Arc;
-use std::time::Duration;
-use sc_client_api::{ExecutorProvider, RemoteBackend};
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use codec::Decode;
+use futures::{Stream, StreamExt};
+use sc_client_api::{ExecutorProvider, RemoteBackend, StorageProvider};
+use node_template_runtime::{self, opaque::Block, Hash, RuntimeApi, SLOT_DURATION};
+use sc_consensus_manual_seal::{EngineCommand, ManualSealParams};
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
-use sp_inherents::InherentDataProviders;
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_inherents::{InherentData, InherentDataProviders, InherentIdentifier, ProvideInherentData};
+use sp_runtime::generic::BlockId;
+use sp_transaction_pool::TransactionPool;
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::{AuthorityPair as AuraPair};
 use sc_finality_grandpa::{FinalityProofProvider as GrandpaFinalityProofProvider, SharedVoterState};
+use crate::chain_spec::GrandpaSettings;
 
 // Our native executor instance.
 native_executor_instance!(
@@ -79,8 +88,8 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	})
 }
 
-/// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+/// Builds a new service for a full client, running GRANDPA with the given `grandpa` settings.
+pub fn new_full(config: Configuration, grandpa: GrandpaSettings) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client, backend, mut task_manager, import_queue, keystore, select_chain, transaction_pool,
 		inherent_data_providers,
@@ -116,18 +125,32 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let telemetry_connection_sinks = sc_service::TelemetryConnectionSinks::default();
 
+	let shared_voter_state = SharedVoterState::empty();
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let justification_stream = grandpa_link.justification_stream();
+		let shared_authority_set = grandpa_link.shared_authority_set().clone();
+		let shared_voter_state = shared_voter_state.clone();
+		let finality_proof_provider = finality_proof_provider.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				command_sink: None,
+				grandpa: Some(crate::rpc::GrandpaDeps {
+					shared_voter_state: shared_voter_state.clone(),
+					shared_authority_set: shared_authority_set.clone(),
+					justification_stream: justification_stream.clone(),
+					subscription_executor,
+					finality_proof_provider: finality_proof_provider.clone(),
+				}),
 			};
 
-			crate::rpc::create_full(deps)
+			crate::rpc::create_full::<_, _, FullBackend>(deps)
 		})
 	};
 
@@ -181,11 +204,10 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	};
 
 	let grandpa_config = sc_finality_grandpa::Config {
-		// FIXME #1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(333),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(grandpa.gossip_duration_ms),
+		justification_period: grandpa.justification_period,
 		name: Some(name),
-		observer_enabled: false,
+		observer_enabled: grandpa.observer_enabled,
 		keystore,
 		is_authority: role.is_network_authority(),
 	};
@@ -205,7 +227,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			telemetry_on_connect: Some(telemetry_connection_sinks.on_connect_stream()),
 			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
 			prometheus_registry,
-			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_state,
 		};
 
 		// the GRANDPA voter task is considered infallible, i.e.
@@ -226,6 +248,180 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	Ok(task_manager)
 }
 
+/// How blocks are sealed by a node running without Aura and GRANDPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal and finalize a block whenever a transaction is imported into the pool.
+	Instant,
+	/// Seal blocks only when asked to through the `engine_createBlock` RPC.
+	Manual,
+}
+
+/// Provides timestamps that advance by one Aura slot per block, so that blocks can be sealed
+/// faster than `SLOT_DURATION` without tripping the runtime's one-block-per-slot check.
+struct MockTimestampInherentDataProvider {
+	next: AtomicU64,
+}
+
+impl MockTimestampInherentDataProvider {
+	/// Continues one slot after the best block's `Timestamp::now`, so that a node restarted on
+	/// an existing chain never goes back to an earlier slot. A fresh chain starts from the
+	/// current time.
+	fn new(client: &FullClient) -> Result<Self, ServiceError> {
+		let best = BlockId::Hash(client.info().best_hash);
+		let key = StorageKey([twox_128(b"Timestamp"), twox_128(b"Now")].concat());
+		let last = client.storage(&best, &key)?
+			.map(|data| u64::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|e| format!("failed to decode the best block's timestamp: {}", e))?;
+
+		let next = match last {
+			Some(last) => last.saturating_add(SLOT_DURATION),
+			None => SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.expect("Current time is always after unix epoch; qed")
+				.as_millis() as u64,
+		};
+
+		Ok(Self { next: AtomicU64::new(next) })
+	}
+}
+
+impl ProvideInherentData for MockTimestampInherentDataProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&sp_timestamp::INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+		let timestamp = self.next.fetch_add(SLOT_DURATION, Ordering::SeqCst);
+		inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		sp_timestamp::InherentDataProvider.error_to_string(error)
+	}
+}
+
+/// Builds a new service for a full client that seals blocks on demand instead of running
+/// Aura and GRANDPA. Only meant for development and integration tests.
+pub fn new_manual_seal(config: Configuration, sealing: Sealing) -> Result<TaskManager, ServiceError> {
+	let (client, backend, keystore, mut task_manager) =
+		sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
+	let client = Arc::new(client);
+
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+		config.transaction_pool.clone(),
+		config.prometheus_registry(),
+		task_manager.spawn_handle(),
+		client.clone(),
+	);
+
+	let inherent_data_providers = InherentDataProviders::new();
+	inherent_data_providers
+		.register_provider(MockTimestampInherentDataProvider::new(&client)?)
+		.map_err(|e| format!("{:?}", e))?;
+
+	let import_queue = sc_consensus_manual_seal::import_queue(
+		Box::new(client.clone()),
+		&task_manager.spawn_handle(),
+		config.prometheus_registry(),
+	);
+
+	let (network, network_status_sinks, system_rpc_tx, network_starter) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			on_demand: None,
+			block_announce_validator_builder: None,
+			finality_proof_request_builder: None,
+			finality_proof_provider: None,
+		})?;
+
+	if config.offchain_worker.enabled {
+		sc_service::build_offchain_workers(
+			&config, backend.clone(), task_manager.spawn_handle(), client.clone(), network.clone(),
+		);
+	}
+
+	let proposer = sc_basic_authorship::ProposerFactory::new(
+		client.clone(),
+		transaction_pool.clone(),
+		config.prometheus_registry(),
+	);
+
+	let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let pool = transaction_pool.clone();
+
+		Box::new(move |deny_unsafe, _| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: Some(command_sink.clone()),
+				grandpa: None,
+			};
+
+			crate::rpc::create_full::<_, _, FullBackend>(deps)
+		})
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		network,
+		client: client.clone(),
+		keystore,
+		task_manager: &mut task_manager,
+		transaction_pool: transaction_pool.clone(),
+		telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
+		rpc_extensions_builder,
+		on_demand: None,
+		remote_blockchain: None,
+		backend, network_status_sinks, system_rpc_tx, config,
+	})?;
+
+	// Blocks requested over RPC are sealed in both modes.
+	let commands_stream: Box<dyn Stream<Item = EngineCommand<Hash>> + Send + Unpin> = match sealing {
+		Sealing::Manual => Box::new(rpc_commands),
+		Sealing::Instant => {
+			let imported = transaction_pool.import_notification_stream().map(|_| {
+				EngineCommand::SealNewBlock {
+					create_empty: false,
+					finalize: true,
+					parent_hash: None,
+					sender: None,
+				}
+			});
+
+			Box::new(futures::stream::select(rpc_commands, imported))
+		},
+	};
+
+	let authorship_future = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer,
+		client,
+		pool: transaction_pool.pool().clone(),
+		commands_stream,
+		select_chain,
+		consensus_data_provider: None,
+		inherent_data_providers,
+	});
+
+	// the sealing task is considered essential, i.e. if it
+	// fails we take down the service with it.
+	task_manager.spawn_essential_handle().spawn_blocking("manual-seal", authorship_future);
+
+	network_starter.start_network();
+	Ok(task_manager)
+}
+
 /// Builds a new service for a light client.
 pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let (client, backend, keystore, mut task_manager, on_demand) =
@@ -281,12 +477,21 @@ pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
 		);
 	}
 
+	let light_deps = crate::rpc::LightDeps {
+		remote_blockchain: backend.remote_blockchain(),
+		fetcher: on_demand.clone(),
+		client: client.clone(),
+		pool: transaction_pool.clone(),
+	};
+
+	let rpc_extensions = crate::rpc::create_light(light_deps);
+
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		remote_blockchain: Some(backend.remote_blockchain()),
 		transaction_pool,
 		task_manager: &mut task_manager,
 		on_demand: Some(on_demand),
-		rpc_extensions_builder: Box::new(|_, _| ()),
+		rpc_extensions_builder: Box::new(sc_service::NoopRpcExtensionBuilder(rpc_extensions)),
 		telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
 		config,
 		client,
@@ -0,0 +1,143 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Pauses whole pallets or individual calls in an emergency.
+//!
+//! `PauseOrigin` pauses and resumes calls by the pallet and call names used in the runtime
+//! metadata, e.g. `KittiesModule` and `buy_kitty`. The module implements `Filter` over any call
+//! type implementing `GetCallMetadata`; set it as `frame_system::Trait::BaseCallFilter` to reject
+//! paused calls during dispatch. Calls dispatched with the root origin bypass the filter.
+//!
+//! Pallets listed in `UnpausablePallets` can never be paused. This should include at least
+//! `System`, any pallet providing inherents and the circuit breaker itself.
+
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure,
+	traits::{CallMetadata, EnsureOrigin, Filter, Get, GetCallMetadata},
+};
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// Origin allowed to pause and resume pallets and calls.
+	type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Names of the pallets that can never be paused.
+	type UnpausablePallets: Get<Vec<Vec<u8>>>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as CircuitBreaker {
+		/// Pallets all of whose calls are paused.
+		pub PausedPallets get(fn paused_pallets): map hasher(blake2_128_concat) Vec<u8> => Option<()>;
+		/// Paused calls, by pallet and call name.
+		pub PausedCalls get(fn paused_calls):
+			double_map hasher(blake2_128_concat) Vec<u8>, hasher(blake2_128_concat) Vec<u8> => Option<()>;
+	}
+}
+
+decl_event! {
+	pub enum Event {
+		/// All calls of a pallet were paused. [pallet]
+		PalletPaused(Vec<u8>),
+		/// A paused pallet was resumed. [pallet]
+		PalletResumed(Vec<u8>),
+		/// A call was paused. [pallet, call]
+		CallPaused(Vec<u8>, Vec<u8>),
+		/// A paused call was resumed. [pallet, call]
+		CallResumed(Vec<u8>, Vec<u8>),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The pallet is listed in `UnpausablePallets`.
+		Unpausable,
+		/// The pallet or call is already paused.
+		AlreadyPaused,
+		/// The pallet or call is not paused.
+		NotPaused,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Pauses all calls of `pallet`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn pause_pallet(origin, pallet: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!T::UnpausablePallets::get().contains(&pallet), Error::<T>::Unpausable);
+			ensure!(!PausedPallets::contains_key(&pallet), Error::<T>::AlreadyPaused);
+
+			PausedPallets::insert(&pallet, ());
+
+			Self::deposit_event(Event::PalletPaused(pallet));
+		}
+
+		/// Resumes a pallet paused with `pause_pallet`. Calls paused individually stay paused.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn resume_pallet(origin, pallet: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(PausedPallets::contains_key(&pallet), Error::<T>::NotPaused);
+
+			PausedPallets::remove(&pallet);
+
+			Self::deposit_event(Event::PalletResumed(pallet));
+		}
+
+		/// Pauses the call `call` of `pallet`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn pause_call(origin, pallet: Vec<u8>, call: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!T::UnpausablePallets::get().contains(&pallet), Error::<T>::Unpausable);
+			ensure!(!PausedCalls::contains_key(&pallet, &call), Error::<T>::AlreadyPaused);
+
+			PausedCalls::insert(&pallet, &call, ());
+
+			Self::deposit_event(Event::CallPaused(pallet, call));
+		}
+
+		/// Resumes a call paused with `pause_call`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn resume_call(origin, pallet: Vec<u8>, call: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(PausedCalls::contains_key(&pallet, &call), Error::<T>::NotPaused);
+
+			PausedCalls::remove(&pallet, &call);
+
+			Self::deposit_event(Event::CallResumed(pallet, call));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether `function_name` of `pallet_name` is currently paused.
+	pub fn is_paused(pallet_name: &str, function_name: &str) -> bool {
+		let pallet = pallet_name.as_bytes();
+		if T::UnpausablePallets::get().iter().any(|p| p.as_slice() == pallet) {
+			return false;
+		}
+
+		PausedPallets::contains_key(pallet) || PausedCalls::contains_key(pallet, function_name.as_bytes())
+	}
+}
+
+impl<T: Trait, C: GetCallMetadata> Filter<C> for Module<T> {
+	fn filter(call: &C) -> bool {
+		let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+		!Self::is_paused(pallet_name, function_name)
+	}
+}
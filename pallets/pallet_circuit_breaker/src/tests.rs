@@ -0,0 +1,146 @@
+use crate::{Error, mock::*};
+use frame_support::{
+	assert_ok, assert_noop,
+	traits::{CallMetadata, Filter, GetCallMetadata},
+};
+use sp_runtime::DispatchError;
+
+/// A call identified only by its metadata.
+struct MockCall(&'static str, &'static str);
+
+impl GetCallMetadata for MockCall {
+	fn get_module_names() -> &'static [&'static str] {
+		&[]
+	}
+
+	fn get_call_names(_: &str) -> &'static [&'static str] {
+		&[]
+	}
+
+	fn get_call_metadata(&self) -> CallMetadata {
+		CallMetadata { pallet_name: self.0, function_name: self.1 }
+	}
+}
+
+fn allowed(pallet: &'static str, call: &'static str) -> bool {
+	<CircuitBreaker as Filter<MockCall>>::filter(&MockCall(pallet, call))
+}
+
+#[test]
+fn nothing_is_paused_by_default() {
+	new_test_ext().execute_with(|| {
+		assert!(allowed("KittiesModule", "buy_kitty"));
+		assert!(allowed("System", "remark"));
+	});
+}
+
+#[test]
+fn pause_pallet_rejects_all_its_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CircuitBreaker::pause_pallet(Origin::root(), b"KittiesModule".to_vec()));
+
+		assert!(!allowed("KittiesModule", "buy_kitty"));
+		assert!(!allowed("KittiesModule", "create_kitty"));
+		assert!(allowed("PoeModule", "create_claim"));
+
+		assert_ok!(CircuitBreaker::resume_pallet(Origin::root(), b"KittiesModule".to_vec()));
+
+		assert!(allowed("KittiesModule", "buy_kitty"));
+	});
+}
+
+#[test]
+fn pause_call_rejects_only_that_call() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CircuitBreaker::pause_call(
+			Origin::root(),
+			b"KittiesModule".to_vec(),
+			b"buy_kitty".to_vec(),
+		));
+
+		assert!(!allowed("KittiesModule", "buy_kitty"));
+		assert!(allowed("KittiesModule", "create_kitty"));
+
+		assert_ok!(CircuitBreaker::resume_call(
+			Origin::root(),
+			b"KittiesModule".to_vec(),
+			b"buy_kitty".to_vec(),
+		));
+
+		assert!(allowed("KittiesModule", "buy_kitty"));
+	});
+}
+
+#[test]
+fn resuming_a_pallet_keeps_paused_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CircuitBreaker::pause_call(
+			Origin::root(),
+			b"KittiesModule".to_vec(),
+			b"buy_kitty".to_vec(),
+		));
+		assert_ok!(CircuitBreaker::pause_pallet(Origin::root(), b"KittiesModule".to_vec()));
+		assert_ok!(CircuitBreaker::resume_pallet(Origin::root(), b"KittiesModule".to_vec()));
+
+		assert!(!allowed("KittiesModule", "buy_kitty"));
+		assert!(allowed("KittiesModule", "create_kitty"));
+	});
+}
+
+#[test]
+fn unpausable_pallets_cannot_be_paused() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CircuitBreaker::pause_pallet(Origin::root(), b"System".to_vec()),
+			Error::<Test>::Unpausable
+		);
+		assert_noop!(
+			CircuitBreaker::pause_call(Origin::root(), b"System".to_vec(), b"remark".to_vec()),
+			Error::<Test>::Unpausable
+		);
+		assert_noop!(
+			CircuitBreaker::pause_pallet(Origin::root(), b"CircuitBreaker".to_vec()),
+			Error::<Test>::Unpausable
+		);
+	});
+}
+
+#[test]
+fn pausing_twice_fails() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CircuitBreaker::pause_pallet(Origin::root(), b"KittiesModule".to_vec()));
+
+		assert_noop!(
+			CircuitBreaker::pause_pallet(Origin::root(), b"KittiesModule".to_vec()),
+			Error::<Test>::AlreadyPaused
+		);
+	});
+}
+
+#[test]
+fn resuming_unpaused_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CircuitBreaker::resume_pallet(Origin::root(), b"KittiesModule".to_vec()),
+			Error::<Test>::NotPaused
+		);
+		assert_noop!(
+			CircuitBreaker::resume_call(Origin::root(), b"KittiesModule".to_vec(), b"buy_kitty".to_vec()),
+			Error::<Test>::NotPaused
+		);
+	});
+}
+
+#[test]
+fn requires_pause_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CircuitBreaker::pause_pallet(Origin::signed(1), b"KittiesModule".to_vec()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			CircuitBreaker::pause_call(Origin::signed(1), b"KittiesModule".to_vec(), b"buy_kitty".to_vec()),
+			DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,99 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Vested transfers made by a privileged origin, for token distributions.
+//!
+//! `pallet_vesting::vested_transfer` must be signed by the account paying for the transfer,
+//! which rules out root-only governance such as sudo and referenda. `force_vested_transfer`
+//! lets `ForceOrigin` move funds out of any account, e.g. the treasury, into a new vesting
+//! schedule, the same way `pallet_balances::force_transfer` does for plain transfers.
+
+use frame_support::{
+	decl_module, decl_event, decl_error, ensure,
+	traits::{Currency, EnsureOrigin, ExistenceRequirement, Get, VestingSchedule},
+};
+use sp_runtime::traits::StaticLookup;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> = <<<T as Trait>::Vesting as VestingSchedule<<T as frame_system::Trait>::AccountId>>::Currency
+	as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The vesting schedules new transfers are added to.
+	type Vesting: VestingSchedule<Self::AccountId, Moment = Self::BlockNumber>;
+
+	/// Origin allowed to make vested transfers from any account.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The minimum amount to be locked by a vested transfer.
+	type MinVestedTransfer: Get<BalanceOf<Self>>;
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// Funds were transferred into a new vesting schedule. [source, target, locked]
+		VestedTransfer(AccountId, AccountId, Balance),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The target already has a vesting schedule.
+		ExistingVestingSchedule,
+		/// The amount to lock is below `MinVestedTransfer`.
+		AmountLow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
+
+		fn deposit_event() = default;
+
+		/// Transfers `locked` from `source` to `target`, locked by a vesting schedule that
+		/// unlocks `per_block` each block from `starting_block`.
+		#[weight = 100_000_000 + T::DbWeight::get().reads_writes(4, 4)]
+		fn force_vested_transfer(
+			origin,
+			source: <T::Lookup as StaticLookup>::Source,
+			target: <T::Lookup as StaticLookup>::Source,
+			locked: BalanceOf<T>,
+			per_block: BalanceOf<T>,
+			starting_block: T::BlockNumber,
+		) {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let source = T::Lookup::lookup(source)?;
+			let target = T::Lookup::lookup(target)?;
+
+			ensure!(locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+			ensure!(T::Vesting::vesting_balance(&target).is_none(), Error::<T>::ExistingVestingSchedule);
+
+			<T::Vesting as VestingSchedule<_>>::Currency::transfer(
+				&source,
+				&target,
+				locked,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			T::Vesting::add_vesting_schedule(&target, locked, per_block, starting_block)
+				.expect("no vesting schedule exists as checked above; qed");
+
+			Self::deposit_event(RawEvent::VestedTransfer(source, target, locked));
+		}
+	}
+}
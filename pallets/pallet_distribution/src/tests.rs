@@ -0,0 +1,81 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::{Currency, VestingSchedule}};
+use sp_runtime::DispatchError;
+
+#[test]
+fn force_vested_transfer_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Distribution::force_vested_transfer(Origin::root(), 1, 3, 100, 10, 5));
+
+		assert_eq!(Balances::free_balance(1), 900);
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Vesting::vesting_balance(&3), Some(100));
+	});
+}
+
+#[test]
+fn vested_funds_unlock_over_time() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Distribution::force_vested_transfer(Origin::root(), 1, 3, 100, 10, 5));
+
+		System::set_block_number(5);
+		assert_eq!(Vesting::vesting_balance(&3), Some(100));
+
+		System::set_block_number(8);
+		assert_eq!(Vesting::vesting_balance(&3), Some(70));
+
+		System::set_block_number(15);
+		assert_eq!(Vesting::vesting_balance(&3), Some(0));
+	});
+}
+
+#[test]
+fn vested_funds_cannot_be_transferred_before_unlocking() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Distribution::force_vested_transfer(Origin::root(), 1, 3, 100, 10, 5));
+		let _ = Balances::deposit_creating(&3, 10);
+
+		assert!(Balances::transfer(Origin::signed(3), 1, 20).is_err());
+		assert_ok!(Balances::transfer(Origin::signed(3), 1, 10));
+	});
+}
+
+#[test]
+fn force_vested_transfer_requires_force_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Distribution::force_vested_transfer(Origin::signed(1), 1, 3, 100, 10, 5),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn force_vested_transfer_rejects_existing_schedule() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Distribution::force_vested_transfer(Origin::root(), 1, 2, 100, 10, 5),
+			Error::<Test>::ExistingVestingSchedule
+		);
+	});
+}
+
+#[test]
+fn force_vested_transfer_rejects_small_amounts() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Distribution::force_vested_transfer(Origin::root(), 1, 3, 9, 1, 5),
+			Error::<Test>::AmountLow
+		);
+	});
+}
+
+#[test]
+fn force_vested_transfer_requires_funds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Distribution::force_vested_transfer(Origin::root(), 1, 3, 2_000, 10, 5),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+	});
+}
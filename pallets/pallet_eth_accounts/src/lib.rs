@@ -0,0 +1,147 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Links 20-byte Ethereum addresses to on-chain accounts.
+//!
+//! An account claims an address by submitting an Ethereum `personal_sign` signature, made with
+//! the address's secp256k1 key, over the hex-encoded account id. The runtime then resolves
+//! `MultiAddress::Address20` through `lookup_address`.
+
+use codec::Encode;
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, traits::Get, StorageMap};
+use frame_system::ensure_signed;
+use sp_core::ecdsa;
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub type EthAddress = [u8; 20];
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// Text prepended to the hex-encoded account id in the signed message, so that
+	/// signatures made for other chains or purposes cannot be replayed here.
+	type Prefix: Get<&'static [u8]>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as EthAccounts {
+		Accounts get(fn account_of): map hasher(blake2_128_concat) EthAddress => Option<T::AccountId>;
+		EthAddresses get(fn eth_address_of): map hasher(blake2_128_concat) T::AccountId => Option<EthAddress>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId
+	{
+		/// An account linked an Ethereum address. [who, address]
+		AddressClaimed(AccountId, EthAddress),
+		/// An account unlinked its Ethereum address. [who, address]
+		AddressReleased(AccountId, EthAddress),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The signature could not be recovered to a public key.
+		InvalidSignature,
+		/// The address is already linked to an account.
+		AddressAlreadyClaimed,
+		/// The account is already linked to an address.
+		AccountAlreadyLinked,
+		/// The account is not linked to any address.
+		NotLinked,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Links the address that produced `signature` to the sender.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn claim_address(origin, signature: ecdsa::Signature) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!EthAddresses::<T>::contains_key(&who), Error::<T>::AccountAlreadyLinked);
+
+			let address = Self::recover_signer(&who, &signature).ok_or(Error::<T>::InvalidSignature)?;
+
+			ensure!(!Accounts::<T>::contains_key(&address), Error::<T>::AddressAlreadyClaimed);
+
+			Accounts::<T>::insert(&address, &who);
+			EthAddresses::<T>::insert(&who, &address);
+
+			Self::deposit_event(RawEvent::AddressClaimed(who, address));
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 2)]
+		fn release_address(origin) {
+			let who = ensure_signed(origin)?;
+
+			let address = EthAddresses::<T>::take(&who).ok_or(Error::<T>::NotLinked)?;
+			Accounts::<T>::remove(&address);
+
+			Self::deposit_event(RawEvent::AddressReleased(who, address));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Returns the account linked to `address`, if any.
+	pub fn lookup_address(address: &EthAddress) -> Option<T::AccountId> {
+		Accounts::<T>::get(address)
+	}
+
+	/// The `personal_sign` message an Ethereum wallet signs to link its address to `who`.
+	pub fn signable_message(who: &T::AccountId) -> Vec<u8> {
+		let prefix = T::Prefix::get();
+		let account = to_ascii_hex(&who.encode());
+
+		let mut length = Vec::new();
+		let mut n = prefix.len() + account.len();
+		loop {
+			length.push(b'0' + (n % 10) as u8);
+			n /= 10;
+			if n == 0 { break; }
+		}
+		length.reverse();
+
+		let mut message = b"\x19Ethereum Signed Message:\n".to_vec();
+		message.extend_from_slice(&length);
+		message.extend_from_slice(prefix);
+		message.extend_from_slice(&account);
+		message
+	}
+
+	fn recover_signer(who: &T::AccountId, signature: &ecdsa::Signature) -> Option<EthAddress> {
+		let message = keccak_256(&Self::signable_message(who));
+		let public = secp256k1_ecdsa_recover(signature.as_ref(), &message).ok()?;
+
+		let mut address = EthAddress::default();
+		address.copy_from_slice(&keccak_256(&public[..])[12..]);
+		Some(address)
+	}
+}
+
+fn to_ascii_hex(data: &[u8]) -> Vec<u8> {
+	let mut hex = Vec::with_capacity(data.len() * 2);
+	for byte in data {
+		for nibble in &[byte >> 4, byte & 0xf] {
+			hex.push(if *nibble < 10 { b'0' + nibble } else { b'a' + nibble - 10 });
+		}
+	}
+	hex
+}
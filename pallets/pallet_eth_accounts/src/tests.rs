@@ -0,0 +1,100 @@
+use crate::{Error, EthAddress, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use sp_core::ecdsa;
+use sp_io::hashing::keccak_256;
+
+fn secret(seed: u8) -> secp256k1::SecretKey {
+	secp256k1::SecretKey::parse(&keccak_256(&[seed])).unwrap()
+}
+
+fn eth_address(secret: &secp256k1::SecretKey) -> EthAddress {
+	let public = secp256k1::PublicKey::from_secret_key(secret);
+	let mut address = EthAddress::default();
+	address.copy_from_slice(&keccak_256(&public.serialize()[1..65])[12..]);
+	address
+}
+
+fn sign_for(secret: &secp256k1::SecretKey, who: u64) -> ecdsa::Signature {
+	let message = keccak_256(&EthAccounts::signable_message(&who));
+	let (signature, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(&message), secret);
+
+	let mut raw = [0u8; 65];
+	raw[..64].copy_from_slice(&signature.serialize());
+	raw[64] = recovery_id.serialize();
+	ecdsa::Signature::from_raw(raw)
+}
+
+#[test]
+fn signable_message_uses_personal_sign_format() {
+	new_test_ext().execute_with(|| {
+		let message = EthAccounts::signable_message(&1);
+		let expected = [
+			&b"\x19Ethereum Signed Message:\n37Link to test account:"[..],
+			&b"0100000000000000"[..],
+		].concat();
+
+		assert_eq!(message, expected);
+	});
+}
+
+#[test]
+fn claim_address_links_both_ways() {
+	new_test_ext().execute_with(|| {
+		let secret = secret(1);
+		let address = eth_address(&secret);
+
+		assert_ok!(EthAccounts::claim_address(Origin::signed(1), sign_for(&secret, 1)));
+
+		assert_eq!(EthAccounts::lookup_address(&address), Some(1));
+		assert_eq!(EthAccounts::eth_address_of(1), Some(address));
+	});
+}
+
+#[test]
+fn signature_for_another_account_links_a_different_address() {
+	new_test_ext().execute_with(|| {
+		let secret = secret(1);
+
+		// The signature still recovers to some key, just not the one that signed it.
+		assert_ok!(EthAccounts::claim_address(Origin::signed(2), sign_for(&secret, 1)));
+
+		let linked = EthAccounts::eth_address_of(2).unwrap();
+		assert_ne!(linked, eth_address(&secret));
+		assert_eq!(EthAccounts::lookup_address(&linked), Some(2));
+		assert_eq!(EthAccounts::lookup_address(&eth_address(&secret)), None);
+	});
+}
+
+#[test]
+fn claim_address_rejects_duplicates() {
+	new_test_ext().execute_with(|| {
+		let secret = secret(1);
+		assert_ok!(EthAccounts::claim_address(Origin::signed(1), sign_for(&secret, 1)));
+
+		assert_noop!(
+			EthAccounts::claim_address(Origin::signed(1), sign_for(&secret(2), 1)),
+			Error::<Test>::AccountAlreadyLinked
+		);
+		assert_noop!(
+			EthAccounts::claim_address(Origin::signed(2), sign_for(&secret, 2)),
+			Error::<Test>::AddressAlreadyClaimed
+		);
+
+		assert_ok!(EthAccounts::release_address(Origin::signed(1)));
+		assert_ok!(EthAccounts::claim_address(Origin::signed(1), sign_for(&secret, 1)));
+	});
+}
+
+#[test]
+fn release_address_unlinks() {
+	new_test_ext().execute_with(|| {
+		let secret = secret(1);
+		assert_noop!(EthAccounts::release_address(Origin::signed(1)), Error::<Test>::NotLinked);
+
+		assert_ok!(EthAccounts::claim_address(Origin::signed(1), sign_for(&secret, 1)));
+		assert_ok!(EthAccounts::release_address(Origin::signed(1)));
+
+		assert_eq!(EthAccounts::lookup_address(&eth_address(&secret)), None);
+		assert_eq!(EthAccounts::eth_address_of(1), None);
+	});
+}
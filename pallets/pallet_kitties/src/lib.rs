@@ -6,22 +6,96 @@ use frame_support::{
 	decl_event,
 	decl_module,
 	decl_storage,
-	traits::{Randomness, Currency, ExistenceRequirement},
-	ensure
+	dispatch::{DispatchError, DispatchResult, Parameter},
+	traits::{ExistenceRequirement, Get, Randomness},
+	ensure, transactional,
 };
 use frame_system::{self as system, ensure_signed};
+use sp_runtime::{
+	ModuleId,
+	helpers_128bit::multiply_by_rational,
+	traits::{AccountIdConversion, AtLeast32BitUnsigned, Member, SaturatedConversion, Zero},
+};
 use sp_std::{vec::Vec};
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
 	type RandomnessSource: Randomness<Self::Hash>;
 
-	type Currency: Currency<Self::AccountId>;
+	/// The currencies kitties can be sold for.
+	type Currency: MultiCurrency<Self::AccountId>;
+
+	/// The currency of listings that do not name one, usually the native token. Buyouts of
+	/// fractionalized kitties are also paid in it.
+	type GetNativeCurrencyId: Get<CurrencyIdOf<Self>>;
+
+	/// The tokens shares of fractionalized kitties are minted in.
+	type Shares: Fungibles<Self::AccountId, Balance = BalanceOf<Self>>;
+
+	/// The pallet's id, used to derive the account holding fractionalized kitties and buyout
+	/// bids.
+	type ModuleId: Get<ModuleId>;
+
+	/// How long a buyout auction runs from the first bid.
+	type BuyoutPeriod: Get<Self::BlockNumber>;
+}
+
+/// Fungible currencies identified by a `CurrencyId`, e.g. the native token and the tokens of
+/// `pallet_assets`.
+pub trait MultiCurrency<AccountId> {
+	/// Identifies a currency.
+	type CurrencyId: Parameter + Member + Copy;
+
+	/// The balance of an account, in any currency.
+	type Balance: Parameter + Member + AtLeast32BitUnsigned + Copy + Default;
+
+	/// The transferable balance of `who` in `currency_id`.
+	fn free_balance(currency_id: Self::CurrencyId, who: &AccountId) -> Self::Balance;
+
+	/// Transfers `amount` of `currency_id` from `from` to `to`. `existence_requirement` only
+	/// applies to currencies with an existential deposit.
+	fn transfer(
+		currency_id: Self::CurrencyId,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Self::Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult;
+}
+
+/// Fungible tokens that can be created on demand, e.g. the tokens of `pallet_assets`.
+pub trait Fungibles<AccountId> {
+	/// Identifies a token.
+	type AssetId: Parameter + Member + Copy;
+
+	/// The balance of an account, in any token.
+	type Balance: Parameter + Member + AtLeast32BitUnsigned + Copy + Default;
+
+	/// Creates a new token, minting its whole supply of `amount` to `who`.
+	fn issue(who: &AccountId, amount: Self::Balance) -> Result<Self::AssetId, DispatchError>;
+
+	/// The balance of `who` in token `id`.
+	fn balance(id: Self::AssetId, who: &AccountId) -> Self::Balance;
+
+	/// The total supply of token `id`.
+	fn total_supply(id: Self::AssetId) -> Self::Balance;
+
+	/// Burns the whole balance of `who` in token `id`.
+	fn burn_all(id: Self::AssetId, who: &AccountId) -> DispatchResult;
 }
 
 type KittyIdType = u128;
-type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type BalanceOf<T> = <<T as Trait>::Currency as MultiCurrency<<T as system::Trait>::AccountId>>::Balance;
+type CurrencyIdOf<T> = <<T as Trait>::Currency as MultiCurrency<<T as system::Trait>::AccountId>>::CurrencyId;
+type ShareIdOf<T> = <<T as Trait>::Shares as Fungibles<<T as system::Trait>::AccountId>>::AssetId;
+type VaultOf<T> = Vault<ShareIdOf<T>, <T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
 
 decl_storage! {
 	trait Store for Module<T: Trait> as KittiesModule {
@@ -29,9 +103,14 @@ decl_storage! {
 		Kitties get(fn kitties): map hasher(blake2_128_concat) KittyIdType => Kitty<T::AccountId, T::Hash>;
 
 		KittiesForSale get(fn kitties_for_sale): map hasher(blake2_128_concat) KittyIdType => BalanceOf<T>;
+		/// The currency a kitty is listed in, if not `GetNativeCurrencyId`.
+		ListingCurrency get(fn listing_currency): map hasher(blake2_128_concat) KittyIdType => Option<CurrencyIdOf<T>>;
 
 		Users get(fn user_data): map hasher(blake2_128_concat) T::AccountId => User;
 
+		/// Fractionalized kitties, held by the pallet's account on behalf of their shareholders.
+		Vaults get(fn vaults): map hasher(blake2_128_concat) KittyIdType => Option<VaultOf<T>>;
+
 		Nonce get(fn nonce): u32;
 	}
 }
@@ -67,14 +146,7 @@ decl_module! {
 		fn sell_kitty(origin, kitty_id: KittyIdType, price: BalanceOf<T>) {
 			let account_id = ensure_signed(origin)?;
 
-			ensure!(!KittiesForSale::<T>::contains_key(&kitty_id), Error::<T>::KittyAlreadyForSale);
-
-			let kitty = Kitties::<T>::get(&kitty_id);
-			ensure!(kitty.owner_id == account_id, Error::<T>::NotKittyOwner);
-
-			KittiesForSale::<T>::insert(&kitty_id, &price);
-
-			Self::deposit_event(RawEvent::KittyForSale(account_id, kitty, price));
+			Self::list_kitty(account_id, kitty_id, price, T::GetNativeCurrencyId::get())?;
 		}
 
 		#[weight = 10_000]
@@ -87,6 +159,7 @@ decl_module! {
 			ensure!(kitty.owner_id == account_id, Error::<T>::NotKittyOwner);
 
 			KittiesForSale::<T>::remove(&kitty_id);
+			ListingCurrency::<T>::remove(&kitty_id);
 
 			Self::deposit_event(RawEvent::CancelKittyForSale(account_id, kitty));
 		}
@@ -101,19 +174,211 @@ decl_module! {
 			ensure!(kitty.owner_id != account_id, Error::<T>::OwnerCanNotBuyKitty);
 			
 			let price = KittiesForSale::<T>::get(&kitty_id);
-			T::Currency::transfer(&account_id, &kitty.owner_id, price, ExistenceRequirement::KeepAlive)?;
+			let currency_id = Self::listing_currency(&kitty_id).unwrap_or_else(T::GetNativeCurrencyId::get);
+			T::Currency::transfer(currency_id, &account_id, &kitty.owner_id, price, ExistenceRequirement::KeepAlive)?;
 			
 			KittiesForSale::<T>::remove(&kitty_id);
+			ListingCurrency::<T>::remove(&kitty_id);
 			kitty.set_owner(account_id.clone());
 			
 			Kitties::<T>::insert(&kitty_id, &kitty);
 
-			Self::deposit_event(RawEvent::KittyWasBought(account_id, kitty, price));
+			Self::deposit_event(RawEvent::KittyWasBought(account_id, kitty, price, currency_id));
+		}
+
+		/// Lists a kitty for sale for `price` in `currency_id`.
+		#[weight = 10_000]
+		fn sell_kitty_for(origin, kitty_id: KittyIdType, price: BalanceOf<T>, currency_id: CurrencyIdOf<T>) {
+			let account_id = ensure_signed(origin)?;
+
+			Self::list_kitty(account_id, kitty_id, price, currency_id)?;
+		}
+
+		/// Locks a kitty in the pallet's account and mints `shares` of a new token to its owner.
+		/// Whoever holds all the shares can `redeem` the kitty. Buyout bids must be at least
+		/// `reserve_price`.
+		#[weight = 10_000]
+		fn fractionalize(origin, kitty_id: KittyIdType, shares: BalanceOf<T>, reserve_price: BalanceOf<T>) {
+			let account_id = ensure_signed(origin)?;
+
+			ensure!(!KittiesForSale::<T>::contains_key(&kitty_id), Error::<T>::KittyAlreadyForSale);
+			ensure!(!shares.is_zero(), Error::<T>::NoShares);
+
+			let mut kitty = Kitties::<T>::get(&kitty_id);
+			ensure!(kitty.owner_id == account_id, Error::<T>::NotKittyOwner);
+
+			let share_id = T::Shares::issue(&account_id, shares)?;
+			kitty.set_owner(Self::account_id());
+			Kitties::<T>::insert(&kitty_id, &kitty);
+			Vaults::<T>::insert(&kitty_id, Vault { shares: share_id, reserve_price, state: VaultState::Locked });
+
+			Self::deposit_event(RawEvent::KittyFractionalized(account_id, kitty_id, share_id, shares));
+		}
+
+		/// Burns all the shares of a fractionalized kitty and hands the kitty to their holder.
+		/// A running buyout auction is cancelled and the highest bid refunded.
+		#[weight = 10_000]
+		#[transactional]
+		fn redeem(origin, kitty_id: KittyIdType) {
+			let account_id = ensure_signed(origin)?;
+
+			let vault = Self::vaults(&kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			ensure!(!matches!(vault.state, VaultState::Bought { .. }), Error::<T>::AuctionEnded);
+
+			let held = T::Shares::balance(vault.shares, &account_id);
+			ensure!(!held.is_zero(), Error::<T>::NoShares);
+			ensure!(held == T::Shares::total_supply(vault.shares), Error::<T>::NotAllShares);
+
+			if let VaultState::Auction { bidder, bid, .. } = vault.state {
+				T::Currency::transfer(
+					T::GetNativeCurrencyId::get(),
+					&Self::account_id(),
+					&bidder,
+					bid,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				Self::deposit_event(RawEvent::BuyoutCancelled(bidder, kitty_id, bid));
+			}
+			T::Shares::burn_all(vault.shares, &account_id)?;
+			Vaults::<T>::remove(&kitty_id);
+
+			let mut kitty = Kitties::<T>::get(&kitty_id);
+			kitty.set_owner(account_id.clone());
+			Kitties::<T>::insert(&kitty_id, &kitty);
+
+			Self::deposit_event(RawEvent::KittyRedeemed(account_id, kitty_id));
+		}
+
+		/// Bids `bid` in the native currency to buy out a fractionalized kitty. The first bid
+		/// must be at least the reserve price and starts an auction running for `BuyoutPeriod`;
+		/// later bids must be higher and refund the previous bidder.
+		#[weight = 10_000]
+		#[transactional]
+		fn bid_buyout(origin, kitty_id: KittyIdType, bid: BalanceOf<T>) {
+			let account_id = ensure_signed(origin)?;
+
+			let mut vault = Self::vaults(&kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			ensure!(!bid.is_zero() && bid >= vault.reserve_price, Error::<T>::BidTooLow);
+
+			let now = system::Module::<T>::block_number();
+			let native = T::GetNativeCurrencyId::get();
+
+			let (previous, end) = match vault.state {
+				VaultState::Locked => (None, now + T::BuyoutPeriod::get()),
+				VaultState::Auction { bidder, bid: highest, end } => {
+					ensure!(now < end, Error::<T>::AuctionEnded);
+					ensure!(bid > highest, Error::<T>::BidTooLow);
+					(Some((bidder, highest)), end)
+				},
+				VaultState::Bought { .. } => Err(Error::<T>::AuctionEnded)?,
+			};
+
+			T::Currency::transfer(native, &account_id, &Self::account_id(), bid, ExistenceRequirement::KeepAlive)?;
+			if let Some((bidder, highest)) = previous {
+				T::Currency::transfer(native, &Self::account_id(), &bidder, highest, ExistenceRequirement::AllowDeath)?;
+			}
+
+			vault.state = VaultState::Auction { bidder: account_id.clone(), bid, end };
+			Vaults::<T>::insert(&kitty_id, vault);
+
+			Self::deposit_event(RawEvent::BuyoutBid(account_id, kitty_id, bid, end));
+		}
+
+		/// Ends a buyout auction after `BuyoutPeriod`, handing the kitty to the highest bidder.
+		/// Shareholders can then `claim_proceeds`.
+		#[weight = 10_000]
+		fn settle_buyout(origin, kitty_id: KittyIdType) {
+			ensure_signed(origin)?;
+
+			let mut vault = Self::vaults(&kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			let (bidder, bid) = match vault.state {
+				VaultState::Auction { bidder, bid, end } => {
+					ensure!(system::Module::<T>::block_number() >= end, Error::<T>::AuctionNotEnded);
+					(bidder, bid)
+				},
+				_ => Err(Error::<T>::NoAuction)?,
+			};
+
+			let mut kitty = Kitties::<T>::get(&kitty_id);
+			kitty.set_owner(bidder.clone());
+			Kitties::<T>::insert(&kitty_id, &kitty);
+
+			vault.state = VaultState::Bought { proceeds: bid };
+			Vaults::<T>::insert(&kitty_id, vault);
+
+			Self::deposit_event(RawEvent::BuyoutSettled(bidder, kitty_id, bid));
+		}
+
+		/// Burns the caller's shares of a bought-out kitty and pays them their share of the
+		/// proceeds, pro rata to the shares still outstanding.
+		#[weight = 10_000]
+		#[transactional]
+		fn claim_proceeds(origin, kitty_id: KittyIdType) {
+			let account_id = ensure_signed(origin)?;
+
+			let mut vault = Self::vaults(&kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			let proceeds = match vault.state {
+				VaultState::Bought { proceeds } => proceeds,
+				_ => Err(Error::<T>::NotBoughtOut)?,
+			};
+
+			let held = T::Shares::balance(vault.shares, &account_id);
+			ensure!(!held.is_zero(), Error::<T>::NoShares);
+			let supply = T::Shares::total_supply(vault.shares);
+
+			let payout: BalanceOf<T> = multiply_by_rational(
+				proceeds.saturated_into(),
+				held.saturated_into(),
+				supply.saturated_into(),
+			).map_err(|_| Error::<T>::Overflow)?.saturated_into();
+
+			T::Currency::transfer(
+				T::GetNativeCurrencyId::get(),
+				&Self::account_id(),
+				&account_id,
+				payout,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			T::Shares::burn_all(vault.shares, &account_id)?;
+
+			if held == supply {
+				Vaults::<T>::remove(&kitty_id);
+			} else {
+				vault.state = VaultState::Bought { proceeds: proceeds - payout };
+				Vaults::<T>::insert(&kitty_id, vault);
+			}
+
+			Self::deposit_event(RawEvent::ProceedsClaimed(account_id, kitty_id, payout));
 		}
 	}
 }
 
 impl<T: Trait> Module<T> {
+	/// The account holding fractionalized kitties and buyout bids.
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	fn list_kitty(
+		account_id: T::AccountId,
+		kitty_id: KittyIdType,
+		price: BalanceOf<T>,
+		currency_id: CurrencyIdOf<T>,
+	) -> DispatchResult {
+		ensure!(!KittiesForSale::<T>::contains_key(&kitty_id), Error::<T>::KittyAlreadyForSale);
+
+		let kitty = Kitties::<T>::get(&kitty_id);
+		ensure!(kitty.owner_id == account_id, Error::<T>::NotKittyOwner);
+
+		KittiesForSale::<T>::insert(&kitty_id, &price);
+		if currency_id != T::GetNativeCurrencyId::get() {
+			ListingCurrency::<T>::insert(&kitty_id, &currency_id);
+		}
+
+		Self::deposit_event(RawEvent::KittyForSale(account_id, kitty, price, currency_id));
+		Ok(())
+	}
+
 	fn encode_and_update_seed() -> Vec<u8> {
 		let nonce = Self::nonce();
 		Nonce::put(nonce.wrapping_add(1));
@@ -139,13 +404,28 @@ decl_event! {
 	where
 		<T as system::Trait>::AccountId,
 		<T as system::Trait>::Hash,
+		<T as system::Trait>::BlockNumber,
 		Balance = BalanceOf<T>,
+		CurrencyId = CurrencyIdOf<T>,
+		ShareId = ShareIdOf<T>,
 	{
 		KittyCreated(AccountId, Kitty<AccountId, Hash>),
-		KittyForSale(AccountId, Kitty<AccountId, Hash>, Balance),
+		KittyForSale(AccountId, Kitty<AccountId, Hash>, Balance, CurrencyId),
 		CancelKittyForSale(AccountId, Kitty<AccountId, Hash>),
 		Transfer(AccountId, AccountId),
-		KittyWasBought(AccountId, Kitty<AccountId, Hash>, Balance),
+		KittyWasBought(AccountId, Kitty<AccountId, Hash>, Balance, CurrencyId),
+		/// A kitty was locked in exchange for shares. [owner, kitty_id, share_id, shares]
+		KittyFractionalized(AccountId, KittyIdType, ShareId, Balance),
+		/// A fractionalized kitty was rebuilt from all its shares. [owner, kitty_id]
+		KittyRedeemed(AccountId, KittyIdType),
+		/// A bid to buy out a fractionalized kitty. [bidder, kitty_id, bid, end]
+		BuyoutBid(AccountId, KittyIdType, Balance, BlockNumber),
+		/// A buyout auction ended. [bidder, kitty_id, bid]
+		BuyoutSettled(AccountId, KittyIdType, Balance),
+		/// A shareholder claimed their part of a buyout. [who, kitty_id, amount]
+		ProceedsClaimed(AccountId, KittyIdType, Balance),
+		/// A buyout auction was cancelled by redeeming the kitty. [bidder, kitty_id, refund]
+		BuyoutCancelled(AccountId, KittyIdType, Balance),
 	}
 }
 
@@ -155,6 +435,24 @@ decl_error! {
 		KittyIsNotForSale,
 		NotKittyOwner,
 		OwnerCanNotBuyKitty,
+		/// The kitty is not fractionalized.
+		NotFractionalized,
+		/// No shares to mint, or the caller holds none.
+		NoShares,
+		/// The caller does not hold all the shares.
+		NotAllShares,
+		/// No buyout auction is running.
+		NoAuction,
+		/// The buyout auction has ended.
+		AuctionEnded,
+		/// The buyout auction is still running.
+		AuctionNotEnded,
+		/// The bid is zero, below the reserve price or not above the highest bid.
+		BidTooLow,
+		/// The kitty has not been bought out.
+		NotBoughtOut,
+		/// The payout does not fit in a balance.
+		Overflow,
 	}
 }
 
@@ -188,4 +486,24 @@ impl User {
 	pub fn add_kitty(&mut self, kitty_id: KittyIdType) {
 		self.kitties.push(kitty_id);
 	}
+}
+
+/// A fractionalized kitty and the token of its shares.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct Vault<AssetId, AccountId, Balance, BlockNumber> {
+	pub shares: AssetId,
+	/// The lowest bid that can start a buyout auction.
+	pub reserve_price: Balance,
+	pub state: VaultState<AccountId, Balance, BlockNumber>,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub enum VaultState<AccountId, Balance, BlockNumber> {
+	/// Shares can be redeemed or a buyout auction started.
+	Locked,
+	/// A buyout auction, holding the highest bid until `end`. Shares can still be redeemed,
+	/// which cancels the auction.
+	Auction { bidder: AccountId, bid: Balance, end: BlockNumber },
+	/// The kitty was bought out; shareholders can claim what is left of the `proceeds`.
+	Bought { proceeds: Balance },
 }
\ No newline at end of file
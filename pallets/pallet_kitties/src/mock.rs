@@ -0,0 +1,168 @@
+use crate::{Fungibles, Module, MultiCurrency, Trait};
+use codec::{Decode, Encode};
+use sp_core::H256;
+use frame_support::{
+	impl_outer_origin, parameter_types,
+	dispatch::{DispatchError, DispatchResult},
+	traits::{Currency, ExistenceRequirement},
+	weights::Weight,
+};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, ModuleId, Perbill, RuntimeDebug,
+};
+use frame_system as system;
+
+impl_outer_origin! {
+	pub enum Origin for Test {}
+}
+
+// Configure a mock runtime to test the pallet.
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const ExistentialDeposit: u64 = 5;
+	pub const GetNativeCurrencyId: CurrencyId = CurrencyId::Native;
+	pub const KittiesModuleId: ModuleId = ModuleId(*b"py/kitty");
+	pub const BuyoutPeriod: u64 = 10;
+}
+
+impl system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+impl pallet_balances::Trait for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_assets::Trait for Test {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+}
+
+/// The native token, or a `pallet_assets` token.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CurrencyId {
+	Native,
+	Asset(u32),
+}
+
+pub struct Currencies;
+
+impl MultiCurrency<u64> for Currencies {
+	type CurrencyId = CurrencyId;
+	type Balance = u64;
+
+	fn free_balance(currency_id: CurrencyId, who: &u64) -> u64 {
+		match currency_id {
+			CurrencyId::Native => Balances::free_balance(who),
+			CurrencyId::Asset(id) => Assets::balance(id, *who),
+		}
+	}
+
+	fn transfer(
+		currency_id: CurrencyId,
+		from: &u64,
+		to: &u64,
+		amount: u64,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		match currency_id {
+			CurrencyId::Native =>
+				<Balances as Currency<u64>>::transfer(from, to, amount, existence_requirement),
+			CurrencyId::Asset(id) => Assets::transfer(Origin::signed(*from), id, *to, amount),
+		}
+	}
+}
+
+pub struct Shares;
+
+impl Fungibles<u64> for Shares {
+	type AssetId = u32;
+	type Balance = u64;
+
+	fn issue(who: &u64, amount: u64) -> Result<u32, DispatchError> {
+		let id = Assets::next_asset_id();
+		Assets::issue(Origin::signed(*who), amount)?;
+		Ok(id)
+	}
+
+	fn balance(id: u32, who: &u64) -> u64 {
+		Assets::balance(id, *who)
+	}
+
+	fn total_supply(id: u32) -> u64 {
+		Assets::total_supply(id)
+	}
+
+	fn burn_all(id: u32, who: &u64) -> DispatchResult {
+		Assets::destroy(Origin::signed(*who), id)
+	}
+}
+
+impl Trait for Test {
+	type Event = ();
+	type RandomnessSource = pallet_randomness_collective_flip::Module<Test>;
+	type Currency = Currencies;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type Shares = Shares;
+	type ModuleId = KittiesModuleId;
+	type BuyoutPeriod = BuyoutPeriod;
+}
+
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Assets = pallet_assets::Module<Test>;
+pub type KittiesModule = Module<Test>;
+
+/// The token issued by account 1 in `new_test_ext`.
+pub const GUILD_TOKEN: CurrencyId = CurrencyId::Asset(0);
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Assets::issue(Origin::signed(1), 1_000).unwrap();
+	});
+	ext
+}
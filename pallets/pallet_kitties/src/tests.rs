@@ -0,0 +1,348 @@
+use crate::{Error, Fungibles, MultiCurrency, VaultState, mock::*};
+use frame_support::{assert_ok, assert_noop};
+
+/// Account 2 creates a kitty; returns its id.
+fn create_kitty() -> u128 {
+	assert_ok!(KittiesModule::create_kitty(Origin::signed(2)));
+	KittiesModule::next_kitty_id()
+}
+
+#[test]
+fn sell_kitty_lists_in_native_currency() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+
+		assert_ok!(KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 10));
+
+		assert_eq!(KittiesModule::kitties_for_sale(kitty_id), 10);
+		assert_eq!(KittiesModule::listing_currency(kitty_id), None);
+	});
+}
+
+#[test]
+fn buy_kitty_pays_in_native_currency() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+		assert_ok!(KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 10));
+
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(1), kitty_id));
+
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, 1);
+		assert_eq!(Balances::free_balance(1), 90);
+		assert_eq!(Balances::free_balance(2), 110);
+		assert!(!crate::KittiesForSale::<Test>::contains_key(kitty_id));
+	});
+}
+
+#[test]
+fn buy_kitty_pays_in_listed_asset() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+		assert_ok!(KittiesModule::sell_kitty_for(Origin::signed(2), kitty_id, 300, GUILD_TOKEN));
+		assert_eq!(KittiesModule::listing_currency(kitty_id), Some(GUILD_TOKEN));
+
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(1), kitty_id));
+
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, 1);
+		assert_eq!(Currencies::free_balance(GUILD_TOKEN, &1), 700);
+		assert_eq!(Currencies::free_balance(GUILD_TOKEN, &2), 300);
+		// The native balances are untouched.
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Balances::free_balance(2), 100);
+		assert_eq!(KittiesModule::listing_currency(kitty_id), None);
+	});
+}
+
+#[test]
+fn buy_kitty_fails_without_enough_of_listed_asset() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+		assert_ok!(KittiesModule::sell_kitty_for(Origin::signed(2), kitty_id, 2_000, GUILD_TOKEN));
+
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(1), kitty_id),
+			pallet_assets::Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fails_without_enough_native_currency() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+		assert_ok!(KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 100));
+
+		// Account 1 must stay alive after paying.
+		assert!(KittiesModule::buy_kitty(Origin::signed(1), kitty_id).is_err());
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, 2);
+	});
+}
+
+#[test]
+fn sell_kitty_for_native_currency_stores_no_listing_currency() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+
+		assert_ok!(KittiesModule::sell_kitty_for(Origin::signed(2), kitty_id, 10, CurrencyId::Native));
+
+		assert_eq!(KittiesModule::listing_currency(kitty_id), None);
+	});
+}
+
+#[test]
+fn cancel_sell_kitty_clears_listing_currency() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+		assert_ok!(KittiesModule::sell_kitty_for(Origin::signed(2), kitty_id, 300, GUILD_TOKEN));
+
+		assert_ok!(KittiesModule::cancel_sell_kitty(Origin::signed(2), kitty_id));
+
+		assert_eq!(KittiesModule::listing_currency(kitty_id), None);
+
+		// Listing again without a currency is in the native currency.
+		assert_ok!(KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 10));
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(1), kitty_id));
+		assert_eq!(Balances::free_balance(2), 110);
+	});
+}
+
+#[test]
+fn only_owner_can_list_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+
+		assert_noop!(
+			KittiesModule::sell_kitty_for(Origin::signed(1), kitty_id, 300, GUILD_TOKEN),
+			Error::<Test>::NotKittyOwner
+		);
+	});
+}
+
+#[test]
+fn kitty_cannot_be_listed_twice() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+		assert_ok!(KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 10));
+
+		assert_noop!(
+			KittiesModule::sell_kitty_for(Origin::signed(2), kitty_id, 300, GUILD_TOKEN),
+			Error::<Test>::KittyAlreadyForSale
+		);
+	});
+}
+
+/// Account 2 creates a kitty and splits it into 100 shares with a reserve price of 20, 60 of
+/// which it gives to account 3. Returns the kitty id and the id of its shares.
+fn fractionalize_kitty() -> (u128, u32) {
+	let kitty_id = create_kitty();
+	assert_ok!(KittiesModule::fractionalize(Origin::signed(2), kitty_id, 100, 20));
+	let shares = KittiesModule::vaults(kitty_id).unwrap().shares;
+	assert_ok!(Assets::transfer(Origin::signed(2), shares, 3, 60));
+	(kitty_id, shares)
+}
+
+#[test]
+fn fractionalize_locks_kitty_and_mints_shares() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+
+		assert_ok!(KittiesModule::fractionalize(Origin::signed(2), kitty_id, 100, 20));
+
+		let vault = KittiesModule::vaults(kitty_id).unwrap();
+		assert_eq!(vault.state, VaultState::Locked);
+		assert_eq!(vault.reserve_price, 20);
+		assert_eq!(Shares::balance(vault.shares, &2), 100);
+		assert_eq!(Shares::total_supply(vault.shares), 100);
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, KittiesModule::account_id());
+
+		// The kitty can no longer be sold by its former owner.
+		assert_noop!(
+			KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 10),
+			Error::<Test>::NotKittyOwner
+		);
+	});
+}
+
+#[test]
+fn only_owner_can_fractionalize_unlisted_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty();
+
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(1), kitty_id, 100, 20),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(2), kitty_id, 0, 20),
+			Error::<Test>::NoShares
+		);
+
+		assert_ok!(KittiesModule::sell_kitty(Origin::signed(2), kitty_id, 10));
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(2), kitty_id, 100, 20),
+			Error::<Test>::KittyAlreadyForSale
+		);
+	});
+}
+
+#[test]
+fn redeem_requires_all_shares() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, shares) = fractionalize_kitty();
+
+		assert_noop!(
+			KittiesModule::redeem(Origin::signed(2), kitty_id),
+			Error::<Test>::NotAllShares
+		);
+
+		assert_ok!(Assets::transfer(Origin::signed(3), shares, 2, 60));
+		assert_ok!(KittiesModule::redeem(Origin::signed(2), kitty_id));
+
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, 2);
+		assert_eq!(KittiesModule::vaults(kitty_id), None);
+		assert_eq!(Shares::total_supply(shares), 0);
+	});
+}
+
+#[test]
+fn outbid_bidder_is_refunded() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, _) = fractionalize_kitty();
+
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 20));
+		assert_eq!(Balances::free_balance(1), 80);
+
+		assert_noop!(
+			KittiesModule::bid_buyout(Origin::signed(3), kitty_id, 20),
+			Error::<Test>::BidTooLow
+		);
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(3), kitty_id, 30));
+
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Balances::free_balance(3), 70);
+		assert_eq!(
+			KittiesModule::vaults(kitty_id).unwrap().state,
+			VaultState::Auction { bidder: 3, bid: 30, end: 11 }
+		);
+	});
+}
+
+#[test]
+fn buyout_starts_at_reserve_price() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, _) = fractionalize_kitty();
+
+		assert_noop!(
+			KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 19),
+			Error::<Test>::BidTooLow
+		);
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 20));
+	});
+}
+
+#[test]
+fn holder_of_all_shares_can_redeem_during_buyout() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, shares) = fractionalize_kitty();
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 20));
+
+		assert_noop!(
+			KittiesModule::redeem(Origin::signed(2), kitty_id),
+			Error::<Test>::NotAllShares
+		);
+
+		assert_ok!(Assets::transfer(Origin::signed(3), shares, 2, 60));
+		assert_ok!(KittiesModule::redeem(Origin::signed(2), kitty_id));
+
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, 2);
+		assert_eq!(KittiesModule::vaults(kitty_id), None);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Balances::free_balance(KittiesModule::account_id()), 0);
+	});
+}
+
+#[test]
+fn buyout_ends_after_period() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, shares) = fractionalize_kitty();
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 20));
+
+		assert_noop!(
+			KittiesModule::settle_buyout(Origin::signed(1), kitty_id),
+			Error::<Test>::AuctionNotEnded
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			KittiesModule::bid_buyout(Origin::signed(3), kitty_id, 30),
+			Error::<Test>::AuctionEnded
+		);
+		assert_ok!(KittiesModule::settle_buyout(Origin::signed(2), kitty_id));
+
+		assert_eq!(KittiesModule::kitties(kitty_id).owner_id, 1);
+		assert_eq!(
+			KittiesModule::vaults(kitty_id).unwrap().state,
+			VaultState::Bought { proceeds: 20 }
+		);
+
+		// Gathering all the shares afterwards no longer brings the kitty back.
+		assert_ok!(Assets::transfer(Origin::signed(3), shares, 2, 60));
+		assert_noop!(
+			KittiesModule::redeem(Origin::signed(2), kitty_id),
+			Error::<Test>::AuctionEnded
+		);
+	});
+}
+
+#[test]
+fn shareholders_claim_proceeds_pro_rata() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, shares) = fractionalize_kitty();
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 50));
+
+		assert_noop!(
+			KittiesModule::claim_proceeds(Origin::signed(2), kitty_id),
+			Error::<Test>::NotBoughtOut
+		);
+
+		System::set_block_number(11);
+		assert_ok!(KittiesModule::settle_buyout(Origin::signed(1), kitty_id));
+
+		assert_ok!(KittiesModule::claim_proceeds(Origin::signed(3), kitty_id));
+		assert_eq!(Balances::free_balance(3), 130);
+		assert_eq!(Shares::balance(shares, &3), 0);
+		assert_noop!(
+			KittiesModule::claim_proceeds(Origin::signed(3), kitty_id),
+			Error::<Test>::NoShares
+		);
+
+		assert_ok!(KittiesModule::claim_proceeds(Origin::signed(2), kitty_id));
+		assert_eq!(Balances::free_balance(2), 120);
+
+		assert_eq!(KittiesModule::vaults(kitty_id), None);
+		assert_eq!(Balances::free_balance(KittiesModule::account_id()), 0);
+	});
+}
+
+#[test]
+fn failed_payout_keeps_shares() {
+	new_test_ext().execute_with(|| {
+		let (kitty_id, shares) = fractionalize_kitty();
+		assert_ok!(Assets::transfer(Origin::signed(2), shares, 4, 2));
+		assert_ok!(KittiesModule::bid_buyout(Origin::signed(1), kitty_id, 50));
+		System::set_block_number(11);
+		assert_ok!(KittiesModule::settle_buyout(Origin::signed(1), kitty_id));
+
+		// A payout of 1 cannot create account 4 in the native currency.
+		assert_noop!(
+			KittiesModule::claim_proceeds(Origin::signed(4), kitty_id),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::ExistentialDeposit
+		);
+		assert_eq!(Shares::balance(shares, &4), 2);
+
+		assert_ok!(Balances::transfer(Origin::signed(1), 4, 10));
+		assert_ok!(KittiesModule::claim_proceeds(Origin::signed(4), kitty_id));
+		assert_eq!(Balances::free_balance(4), 11);
+		assert_eq!(Shares::balance(shares, &4), 0);
+	});
+}
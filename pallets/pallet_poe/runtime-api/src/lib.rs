@@ -0,0 +1,13 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait PoeApi {
+		/// Checks that `leaf` is the `index`-th leaf of a claimed Merkle `root`, given the
+		/// sibling hashes produced by `pallet_poe::merkle::MerkleTree::proof`.
+		fn verify_merkle_leaf(root: [u8; 32], leaf: [u8; 32], index: u32, proof: Vec<[u8; 32]>) -> bool;
+	}
+}
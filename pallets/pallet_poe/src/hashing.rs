@@ -0,0 +1,51 @@
+//! Client-side helpers for computing the document digests accepted by `create_claim_for_hash`.
+//!
+//! Wallets and the node CLI should use these rather than hashing by hand, so that the same
+//! document always maps to the same claim.
+
+use sp_core::hashing::{blake2_256, keccak_256, sha2_256};
+use std::{fmt, str::FromStr};
+
+use crate::{DocumentHash, HashAlgorithm};
+
+/// Hashes `data` with `algorithm`.
+pub fn hash_document(algorithm: HashAlgorithm, data: &[u8]) -> [u8; 32] {
+	match algorithm {
+		HashAlgorithm::Blake2_256 => blake2_256(data),
+		HashAlgorithm::Sha2_256 => sha2_256(data),
+		HashAlgorithm::Keccak256 => keccak_256(data),
+	}
+}
+
+impl DocumentHash {
+	/// Hashes `data` with `algorithm` and keys the digest by the algorithm used.
+	pub fn of(algorithm: HashAlgorithm, data: &[u8]) -> Self {
+		DocumentHash { algorithm, digest: hash_document(algorithm, data) }
+	}
+}
+
+impl FromStr for HashAlgorithm {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"blake2-256" => Ok(HashAlgorithm::Blake2_256),
+			"sha2-256" => Ok(HashAlgorithm::Sha2_256),
+			"keccak-256" => Ok(HashAlgorithm::Keccak256),
+			other => Err(format!(
+				"unknown hash algorithm `{}`, expected one of blake2-256, sha2-256, keccak-256",
+				other,
+			)),
+		}
+	}
+}
+
+impl fmt::Display for HashAlgorithm {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			HashAlgorithm::Blake2_256 => "blake2-256",
+			HashAlgorithm::Sha2_256 => "sha2-256",
+			HashAlgorithm::Keccak256 => "keccak-256",
+		})
+	}
+}
@@ -1,11 +1,129 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{ decl_module, decl_storage, decl_event, decl_error, ensure, StorageMap };
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, dispatch::DispatchResult, ensure,
+	traits::Get, weights::Weight, IterableStorageMap, StorageMap, StorageValue,
+};
 use frame_system::ensure_signed;
+use sp_runtime::traits::Zero;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "std")]
+pub mod hashing;
+
+pub mod merkle;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// Maximum length in bytes of a claim's description or URI.
+	type MaxDescriptionLength: Get<u32>;
+
+	/// Maximum length in bytes of a claim's MIME type.
+	type MaxMimeTypeLength: Get<u32>;
+
+	/// Maximum number of claims, and separately of co-signed claims still pending, that can
+	/// expire in the same block.
+	type MaxExpiringPerBlock: Get<u32>;
+
+	/// Maximum number of signers a co-signed claim can require.
+	type MaxSigners: Get<u32>;
+
+	/// Number of blocks a co-signed claim stays open before it expires unfinalized. Must be at
+	/// least one.
+	type PendingClaimLifetime: Get<Self::BlockNumber>;
+}
+
+/// Hash functions a document digest can be computed with.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HashAlgorithm {
+	Blake2_256,
+	Sha2_256,
+	Keccak256,
+}
+
+/// A 32-byte document digest, keyed together with the algorithm that produced it so
+/// the same bytes hashed with different functions never collide.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct DocumentHash {
+	pub algorithm: HashAlgorithm,
+	pub digest: [u8; 32],
+}
+
+/// Identifies a claim in either `Proofs` or `HashedProofs`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub enum ClaimKey {
+	Raw(Vec<u8>),
+	Hashed(DocumentHash),
+}
+
+/// Optional details supplied when a claim is created.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+pub struct ClaimMetadata<BlockNumber> {
+	/// Free-form description of the document, or a URI pointing at it.
+	pub description: Option<Vec<u8>>,
+	/// MIME type of the document, e.g. `application/pdf`.
+	pub mime_type: Option<Vec<u8>>,
+	/// Block at which the claim is removed.
+	pub expires_at: Option<BlockNumber>,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct Claim<AccountId, BlockNumber> {
+	pub owner: AccountId,
+	/// Block at which the claim was created.
+	pub block: BlockNumber,
+	pub metadata: ClaimMetadata<BlockNumber>,
+}
+
+/// A claim on every leaf of a Merkle tree, recorded by its root.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct MerkleClaim<AccountId, BlockNumber> {
+	pub owner: AccountId,
+	/// Block at which the claim was created.
+	pub block: BlockNumber,
+	/// Number of leaves in the tree, needed to verify inclusion proofs.
+	pub leaf_count: u32,
+}
+
+/// A claim waiting for signatures from a set of required signers.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct PendingClaim<AccountId, BlockNumber> {
+	/// Account that opened the claim, and its owner once finalized.
+	pub initiator: AccountId,
+	pub signers: Vec<AccountId>,
+	/// Number of signers that must co-sign before the claim is finalized.
+	pub threshold: u32,
+	/// Signers that have co-signed so far.
+	pub approvals: Vec<AccountId>,
+	pub expires_at: BlockNumber,
+}
+
+pub type ClaimOf<T> = Claim<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber>;
+pub type ClaimMetadataOf<T> = ClaimMetadata<<T as frame_system::Trait>::BlockNumber>;
+pub type PendingClaimOf<T> = PendingClaim<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber>;
+pub type MerkleClaimOf<T> = MerkleClaim<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber>;
+
+/// Storage layout versions of this pallet, used to gate migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Releases {
+	/// Claims stored as `(AccountId, BlockNumber)`.
+	V1,
+	/// Claims stored as `Claim`, with metadata and expiry.
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
 }
 
 decl_event! {
@@ -15,6 +133,22 @@ decl_event! {
 	{
 		ClaimCreated(AccountId, Vec<u8>),
 		ClaimRevoked(AccountId, Vec<u8>),
+		HashClaimCreated(AccountId, DocumentHash),
+		HashClaimRevoked(AccountId, DocumentHash),
+		/// A claim reached its `expires_at` block and was removed. [owner, claim]
+		ClaimExpired(AccountId, ClaimKey),
+		/// A Merkle root was claimed. [owner, root, leaf_count]
+		MerkleClaimCreated(AccountId, [u8; 32], u32),
+		/// A Merkle root claim was revoked. [owner, root]
+		MerkleClaimRevoked(AccountId, [u8; 32]),
+		/// A co-signed claim was opened. [initiator, proof, threshold]
+		ClaimOpened(AccountId, Vec<u8>, u32),
+		/// A signer co-signed an open claim. [signer, proof]
+		ClaimCosigned(AccountId, Vec<u8>),
+		/// A co-signed claim reached its threshold and is now a regular claim. [initiator, proof]
+		ClaimFinalized(AccountId, Vec<u8>),
+		/// A co-signed claim expired before reaching its threshold. [initiator, proof]
+		PendingClaimExpired(AccountId, Vec<u8>),
 	}
 }
 
@@ -23,12 +157,39 @@ decl_error! {
 		ProofAlreadyClaimed,
 		NoSuchProof,
 		NotProofOwner,
+		DescriptionTooLong,
+		MimeTypeTooLong,
+		ExpiryNotInFuture,
+		EmptyMerkleTree,
+		NoSigners,
+		TooManySigners,
+		DuplicateSigner,
+		InvalidThreshold,
+		NoSuchPendingClaim,
+		NotASigner,
+		AlreadyCosigned,
+		/// Too many claims already expire in the requested block.
+		ExpiryBlockFull,
 	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as PoeModule {
-		Proofs: map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		pub Proofs: map hasher(blake2_128_concat) Vec<u8> => Option<ClaimOf<T>>;
+		pub HashedProofs: map hasher(blake2_128_concat) DocumentHash => Option<ClaimOf<T>>;
+
+		pub MerkleRoots: map hasher(blake2_128_concat) [u8; 32] => Option<MerkleClaimOf<T>>;
+
+		/// Claims to remove at the start of a given block, at most `MaxExpiringPerBlock` each.
+		ExpiringClaims: map hasher(twox_64_concat) T::BlockNumber => Vec<ClaimKey>;
+
+		PendingClaims: map hasher(blake2_128_concat) Vec<u8> => Option<PendingClaimOf<T>>;
+		/// Pending claims to drop at the start of a given block, at most `MaxExpiringPerBlock` each.
+		ExpiringPendingClaims: map hasher(twox_64_concat) T::BlockNumber => Vec<Vec<u8>>;
+		/// Accounts that co-signed a finalized claim.
+		CoSigners get(fn cosigners): map hasher(blake2_128_concat) Vec<u8> => Vec<T::AccountId>;
+
+		StorageVersion build(|_: &GenesisConfig| Releases::V2): Releases;
 	}
 }
 
@@ -39,34 +200,351 @@ decl_module! {
 	{
 		type Error = Error<T>;
 
+		const MaxDescriptionLength: u32 = T::MaxDescriptionLength::get();
+		const MaxMimeTypeLength: u32 = T::MaxMimeTypeLength::get();
+		const MaxExpiringPerBlock: u32 = T::MaxExpiringPerBlock::get();
+		const MaxSigners: u32 = T::MaxSigners::get();
+		const PendingClaimLifetime: T::BlockNumber = T::PendingClaimLifetime::get();
+
 		fn deposit_event() = default;
 
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			Self::expire_claims(now).saturating_add(Self::expire_pending_claims(now))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_to_v2()
+		}
+
+		fn integrity_test() {
+			assert!(
+				!T::PendingClaimLifetime::get().is_zero(),
+				"`PendingClaimLifetime` must be at least one block, or pending claims never expire",
+			);
+		}
+
 		#[weight = 10_000]
 		fn create_claim(origin, proof: Vec<u8>) {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(!Proofs::<T>::contains_key(&proof), Error::<T>::ProofAlreadyClaimed);
+			Self::do_create_claim(sender, proof)?;
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 3)]
+		fn revoke_claim(origin, proof: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::remove_claim(sender, ClaimKey::Raw(proof))?;
+		}
+
+		#[weight = 10_000]
+		fn create_claim_for_hash(origin, algorithm: HashAlgorithm, digest: [u8; 32]) {
+			let sender = ensure_signed(origin)?;
+
+			let hash = DocumentHash { algorithm, digest };
+
+			Self::insert_claim(sender, ClaimKey::Hashed(hash), Default::default())?;
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn revoke_claim_for_hash(origin, algorithm: HashAlgorithm, digest: [u8; 32]) {
+			let sender = ensure_signed(origin)?;
+
+			let hash = DocumentHash { algorithm, digest };
+
+			Self::remove_claim(sender, ClaimKey::Hashed(hash))?;
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 2)]
+		fn create_claim_with_metadata(origin, proof: Vec<u8>, metadata: ClaimMetadataOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::insert_claim(sender, ClaimKey::Raw(proof), metadata)?;
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn create_claim_for_hash_with_metadata(
+			origin,
+			algorithm: HashAlgorithm,
+			digest: [u8; 32],
+			metadata: ClaimMetadataOf<T>,
+		) {
+			let sender = ensure_signed(origin)?;
+
+			let hash = DocumentHash { algorithm, digest };
+
+			Self::insert_claim(sender, ClaimKey::Hashed(hash), metadata)?;
+		}
+
+		/// Claims every leaf of the Merkle tree with the given `root` in one go.
+		/// Build the tree and its proofs with `merkle::MerkleTree`.
+		#[weight = 10_000]
+		fn create_merkle_claim(origin, root: [u8; 32], leaf_count: u32) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(leaf_count > 0, Error::<T>::EmptyMerkleTree);
+			ensure!(!MerkleRoots::<T>::contains_key(&root), Error::<T>::ProofAlreadyClaimed);
 
 			let current_block = <frame_system::Module<T>>::block_number();
 
-			Proofs::<T>::insert(&proof, (&sender, current_block));
+			MerkleRoots::<T>::insert(&root, MerkleClaim { owner: sender.clone(), block: current_block, leaf_count });
 
-			Self::deposit_event(RawEvent::ClaimCreated(sender, proof));
+			Self::deposit_event(RawEvent::MerkleClaimCreated(sender, root, leaf_count));
 		}
 
 		#[weight = 10_000]
-		fn revoke_claim(origin, proof: Vec<u8>) {
+		fn revoke_merkle_claim(origin, root: [u8; 32]) {
+			let sender = ensure_signed(origin)?;
+
+			let claim = MerkleRoots::<T>::get(&root).ok_or(Error::<T>::NoSuchProof)?;
+
+			ensure!(claim.owner == sender, Error::<T>::NotProofOwner);
+
+			MerkleRoots::<T>::remove(&root);
+
+			Self::deposit_event(RawEvent::MerkleClaimRevoked(sender, root));
+		}
+
+		/// Opens a claim on `proof` that becomes final once `threshold` of `signers` have
+		/// called `cosign_claim`. The caller owns the finalized claim.
+		#[weight = 10_000
+			+ 1_000 * signers.len() as Weight
+			+ T::DbWeight::get().reads_writes(4, 2)]
+		fn open_cosigned_claim(origin, proof: Vec<u8>, signers: Vec<T::AccountId>, threshold: u32) {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+			ensure!(!signers.is_empty(), Error::<T>::NoSigners);
+			ensure!(signers.len() as u32 <= T::MaxSigners::get(), Error::<T>::TooManySigners);
+			ensure!(threshold > 0 && threshold as usize <= signers.len(), Error::<T>::InvalidThreshold);
+
+			let mut sorted = signers.clone();
+			sorted.sort();
+			sorted.dedup();
+			ensure!(sorted.len() == signers.len(), Error::<T>::DuplicateSigner);
+
+			ensure!(!Proofs::<T>::contains_key(&proof), Error::<T>::ProofAlreadyClaimed);
+			ensure!(!PendingClaims::<T>::contains_key(&proof), Error::<T>::ProofAlreadyClaimed);
+
+			let expires_at = <frame_system::Module<T>>::block_number() + T::PendingClaimLifetime::get();
+			ensure!(
+				ExpiringPendingClaims::<T>::decode_len(expires_at).unwrap_or(0) < T::MaxExpiringPerBlock::get() as usize,
+				Error::<T>::ExpiryBlockFull
+			);
+
+			PendingClaims::<T>::insert(&proof, PendingClaim {
+				initiator: sender.clone(),
+				signers,
+				threshold,
+				approvals: Vec::new(),
+				expires_at,
+			});
+			ExpiringPendingClaims::<T>::append(expires_at, &proof);
+
+			Self::deposit_event(RawEvent::ClaimOpened(sender, proof, threshold));
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 4)]
+		fn cosign_claim(origin, proof: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			let mut pending = PendingClaims::<T>::get(&proof).ok_or(Error::<T>::NoSuchPendingClaim)?;
+
+			ensure!(pending.signers.contains(&sender), Error::<T>::NotASigner);
+			ensure!(!pending.approvals.contains(&sender), Error::<T>::AlreadyCosigned);
+
+			pending.approvals.push(sender.clone());
+
+			Self::deposit_event(RawEvent::ClaimCosigned(sender, proof.clone()));
+
+			if (pending.approvals.len() as u32) < pending.threshold {
+				PendingClaims::<T>::insert(&proof, pending);
+				return Ok(());
+			}
+
+			PendingClaims::<T>::remove(&proof);
+			ExpiringPendingClaims::<T>::mutate(pending.expires_at, |proofs| proofs.retain(|p| p != &proof));
+			CoSigners::<T>::insert(&proof, &pending.approvals);
+
+			Self::insert_claim(pending.initiator.clone(), ClaimKey::Raw(proof.clone()), Default::default())?;
+
+			Self::deposit_event(RawEvent::ClaimFinalized(pending.initiator, proof));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Returns the claim on `proof`, if any.
+	pub fn claim_of(proof: &[u8]) -> Option<ClaimOf<T>> {
+		Proofs::<T>::get(proof)
+	}
+
+	/// Returns the claim on the document `hash`, if any.
+	pub fn hashed_claim_of(hash: &DocumentHash) -> Option<ClaimOf<T>> {
+		HashedProofs::<T>::get(hash)
+	}
+
+	/// Returns the claim on the Merkle `root`, if any.
+	pub fn merkle_claim_of(root: &[u8; 32]) -> Option<MerkleClaimOf<T>> {
+		MerkleRoots::<T>::get(root)
+	}
+
+	/// Checks that `leaf` is the `index`-th leaf of a claimed Merkle `root`.
+	pub fn verify_merkle_leaf(root: [u8; 32], leaf: [u8; 32], index: u32, proof: Vec<[u8; 32]>) -> bool {
+		match MerkleRoots::<T>::get(&root) {
+			Some(claim) => merkle::verify_proof(&root, &leaf, index, claim.leaf_count, &proof),
+			None => false,
+		}
+	}
+
+	/// Records a claim on `proof` owned by `owner` at the current block.
+	///
+	/// `owner` is trusted, so callers must have authenticated it themselves.
+	pub fn do_create_claim(owner: T::AccountId, proof: Vec<u8>) -> DispatchResult {
+		Self::insert_claim(owner, ClaimKey::Raw(proof), Default::default())
+	}
+
+	fn get_claim(key: &ClaimKey) -> Option<ClaimOf<T>> {
+		match key {
+			ClaimKey::Raw(proof) => Proofs::<T>::get(proof),
+			ClaimKey::Hashed(hash) => HashedProofs::<T>::get(hash),
+		}
+	}
+
+	fn insert_claim(owner: T::AccountId, key: ClaimKey, metadata: ClaimMetadataOf<T>) -> DispatchResult {
+		ensure!(Self::get_claim(&key).is_none(), Error::<T>::ProofAlreadyClaimed);
+		if let ClaimKey::Raw(proof) = &key {
+			ensure!(!PendingClaims::<T>::contains_key(proof), Error::<T>::ProofAlreadyClaimed);
+		}
+
+		if let Some(description) = &metadata.description {
+			ensure!(description.len() as u32 <= T::MaxDescriptionLength::get(), Error::<T>::DescriptionTooLong);
+		}
+		if let Some(mime_type) = &metadata.mime_type {
+			ensure!(mime_type.len() as u32 <= T::MaxMimeTypeLength::get(), Error::<T>::MimeTypeTooLong);
+		}
+
+		let current_block = <frame_system::Module<T>>::block_number();
+
+		if let Some(expires_at) = metadata.expires_at {
+			ensure!(expires_at > current_block, Error::<T>::ExpiryNotInFuture);
+			ensure!(
+				ExpiringClaims::<T>::decode_len(expires_at).unwrap_or(0) < T::MaxExpiringPerBlock::get() as usize,
+				Error::<T>::ExpiryBlockFull
+			);
+
+			ExpiringClaims::<T>::append(expires_at, &key);
+		}
+
+		let claim = Claim { owner: owner.clone(), block: current_block, metadata };
+
+		match key {
+			ClaimKey::Raw(proof) => {
+				Proofs::<T>::insert(&proof, claim);
+				Self::deposit_event(RawEvent::ClaimCreated(owner, proof));
+			},
+			ClaimKey::Hashed(hash) => {
+				HashedProofs::<T>::insert(&hash, claim);
+				Self::deposit_event(RawEvent::HashClaimCreated(owner, hash));
+			},
+		}
+
+		Ok(())
+	}
+
+	fn remove_claim(sender: T::AccountId, key: ClaimKey) -> DispatchResult {
+		let claim = Self::get_claim(&key).ok_or(Error::<T>::NoSuchProof)?;
+
+		ensure!(claim.owner == sender, Error::<T>::NotProofOwner);
+
+		if let Some(expires_at) = claim.metadata.expires_at {
+			ExpiringClaims::<T>::mutate(expires_at, |keys| keys.retain(|k| k != &key));
+		}
+
+		match key {
+			ClaimKey::Raw(proof) => {
+				Proofs::<T>::remove(&proof);
+				CoSigners::<T>::remove(&proof);
+				Self::deposit_event(RawEvent::ClaimRevoked(sender, proof));
+			},
+			ClaimKey::Hashed(hash) => {
+				HashedProofs::<T>::remove(&hash);
+				Self::deposit_event(RawEvent::HashClaimRevoked(sender, hash));
+			},
+		}
 
-			let (owner, _) = Proofs::<T>::get(&proof);
+		Ok(())
+	}
+
+	/// Removes every claim scheduled to expire at `now`, at most `MaxExpiringPerBlock`.
+	fn expire_claims(now: T::BlockNumber) -> Weight {
+		let keys = ExpiringClaims::<T>::take(now);
+		let db_weight = T::DbWeight::get();
+		let mut weight = db_weight.reads_writes(1, 1);
+
+		for key in keys {
+			weight = weight.saturating_add(db_weight.reads(1));
 
-			ensure!(owner == sender, Error::<T>::NotProofOwner);
+			let claim = match Self::get_claim(&key) {
+				Some(claim) if claim.metadata.expires_at == Some(now) => claim,
+				_ => continue,
+			};
 
-			Proofs::<T>::remove(&proof);
+			match &key {
+				ClaimKey::Raw(proof) => {
+					Proofs::<T>::remove(proof);
+					CoSigners::<T>::remove(proof);
+					weight = weight.saturating_add(db_weight.writes(2));
+				},
+				ClaimKey::Hashed(hash) => {
+					HashedProofs::<T>::remove(hash);
+					weight = weight.saturating_add(db_weight.writes(1));
+				},
+			}
 
-			Self::deposit_event(RawEvent::ClaimRevoked(sender, proof));
+			Self::deposit_event(RawEvent::ClaimExpired(claim.owner, key));
 		}
+
+		weight
 	}
-}
\ No newline at end of file
+
+	/// Drops every co-signed claim that is still pending at its expiry block `now`, at most
+	/// `MaxExpiringPerBlock`.
+	fn expire_pending_claims(now: T::BlockNumber) -> Weight {
+		let proofs = ExpiringPendingClaims::<T>::take(now);
+		let db_weight = T::DbWeight::get();
+		let mut weight = db_weight.reads_writes(1, 1);
+
+		for proof in proofs {
+			weight = weight.saturating_add(db_weight.reads(1));
+
+			let pending = match PendingClaims::<T>::get(&proof) {
+				Some(pending) if pending.expires_at == now => pending,
+				_ => continue,
+			};
+
+			PendingClaims::<T>::remove(&proof);
+			weight = weight.saturating_add(db_weight.writes(1));
+
+			Self::deposit_event(RawEvent::PendingClaimExpired(pending.initiator, proof));
+		}
+
+		weight
+	}
+
+	/// Converts claims stored as `(AccountId, BlockNumber)` into `Claim`s without metadata.
+	fn migrate_to_v2() -> Weight {
+		if StorageVersion::get() != Releases::V1 {
+			return 0;
+		}
+
+		let upgrade = |(owner, block): (T::AccountId, T::BlockNumber)| {
+			Some(Claim { owner, block, metadata: Default::default() })
+		};
+		Proofs::<T>::translate(|_, old| upgrade(old));
+		HashedProofs::<T>::translate(|_, old| upgrade(old));
+
+		StorageVersion::put(Releases::V2);
+
+		T::MaximumBlockWeight::get()
+	}
+}
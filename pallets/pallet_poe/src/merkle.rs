@@ -0,0 +1,138 @@
+//! Binary Merkle trees over 32-byte leaves, used by `create_merkle_claim`.
+//!
+//! Leaves and inner nodes are hashed with blake2-256 under distinct one-byte prefixes, so an
+//! inner node can never be passed off as a leaf. A node without a sibling at the end of a
+//! level is promoted to the next level unchanged, which is why verification needs the total
+//! leaf count to know at which levels the proof carries a sibling.
+
+use sp_io::hashing::blake2_256;
+#[cfg(feature = "std")]
+use std::{fs, io, path::Path};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+	let mut input = [0u8; 33];
+	input[0] = LEAF_PREFIX;
+	input[1..].copy_from_slice(leaf);
+	blake2_256(&input)
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let mut input = [0u8; 65];
+	input[0] = NODE_PREFIX;
+	input[1..33].copy_from_slice(left);
+	input[33..].copy_from_slice(right);
+	blake2_256(&input)
+}
+
+/// Checks that `leaf` is the `index`-th of `leaf_count` leaves of the tree with root `root`.
+pub fn verify_proof(
+	root: &[u8; 32],
+	leaf: &[u8; 32],
+	mut index: u32,
+	leaf_count: u32,
+	proof: &[[u8; 32]],
+) -> bool {
+	if index >= leaf_count {
+		return false;
+	}
+
+	let mut width = leaf_count;
+	let mut node = hash_leaf(leaf);
+	let mut siblings = proof.iter();
+
+	while width > 1 {
+		if index % 2 == 1 {
+			match siblings.next() {
+				Some(left) => node = hash_node(left, &node),
+				None => return false,
+			}
+		} else if index + 1 < width {
+			match siblings.next() {
+				Some(right) => node = hash_node(&node, right),
+				None => return false,
+			}
+		}
+
+		index /= 2;
+		width = (width + 1) / 2;
+	}
+
+	siblings.next().is_none() && &node == root
+}
+
+/// A fully materialised Merkle tree, for building roots and proofs off-chain.
+#[cfg(feature = "std")]
+pub struct MerkleTree {
+	/// Hashed nodes level by level, from the leaves up to the root.
+	levels: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(feature = "std")]
+impl MerkleTree {
+	/// Builds a tree over `leaves`. Returns `None` if there are no leaves.
+	pub fn from_leaves(leaves: &[[u8; 32]]) -> Option<Self> {
+		if leaves.is_empty() {
+			return None;
+		}
+
+		let mut levels = vec![leaves.iter().map(hash_leaf).collect::<Vec<_>>()];
+		while levels.last().map_or(false, |level| level.len() > 1) {
+			let next = levels.last().expect("checked above; qed")
+				.chunks(2)
+				.map(|pair| match pair {
+					[left, right] => hash_node(left, right),
+					[single] => *single,
+					_ => unreachable!("chunks(2) yields one or two items; qed"),
+				})
+				.collect();
+			levels.push(next);
+		}
+
+		Some(MerkleTree { levels })
+	}
+
+	/// Builds a tree whose leaves are the blake2-256 digests of the given files' contents.
+	pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> io::Result<Self> {
+		let leaves = paths.iter()
+			.map(|path| {
+				fs::read(path).map(|data| {
+					crate::hashing::hash_document(crate::HashAlgorithm::Blake2_256, &data)
+				})
+			})
+			.collect::<io::Result<Vec<_>>>()?;
+
+		Self::from_leaves(&leaves)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no files to build a tree from"))
+	}
+
+	pub fn root(&self) -> [u8; 32] {
+		self.levels.last().expect("a tree always has at least one level; qed")[0]
+	}
+
+	pub fn leaf_count(&self) -> u32 {
+		self.levels[0].len() as u32
+	}
+
+	/// Returns the sibling hashes proving the `index`-th leaf, in the order `verify_proof` expects.
+	pub fn proof(&self, index: u32) -> Option<Vec<[u8; 32]>> {
+		if index >= self.leaf_count() {
+			return None;
+		}
+
+		let mut index = index as usize;
+		let mut proof = Vec::new();
+		for level in &self.levels[..self.levels.len() - 1] {
+			if index % 2 == 1 {
+				proof.push(level[index - 1]);
+			} else if index + 1 < level.len() {
+				proof.push(level[index + 1]);
+			}
+			index /= 2;
+		}
+
+		Some(proof)
+	}
+}
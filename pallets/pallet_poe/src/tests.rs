@@ -0,0 +1,456 @@
+use crate::{
+	Error, ClaimKey, ClaimMetadata, DocumentHash, ExpiringClaims, HashAlgorithm, HashedProofs, Proofs,
+	Releases, StorageVersion,
+	hashing::hash_document, merkle::{MerkleTree, verify_proof}, mock::*,
+};
+use frame_support::{
+	assert_ok, assert_noop, storage::unhashed,
+	traits::{OnInitialize, OnRuntimeUpgrade},
+	StorageMap, StorageValue,
+};
+use hex_literal::hex;
+
+#[test]
+fn create_claim_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1, 2, 3]));
+
+		assert_eq!(PoeModule::claim_of(&[1, 2, 3]).map(|c| (c.owner, c.block)), Some((1, 5)));
+	});
+}
+
+#[test]
+fn create_claim_fails_when_already_claimed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1, 2, 3]));
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(2), vec![1, 2, 3]),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn revoke_claim_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1, 2, 3]));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![1, 2, 3]));
+
+		assert_eq!(PoeModule::claim_of(&[1, 2, 3]), None);
+	});
+}
+
+#[test]
+fn revoke_claim_fails_for_non_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1, 2, 3]));
+
+		assert_noop!(
+			PoeModule::revoke_claim(Origin::signed(2), vec![1, 2, 3]),
+			Error::<Test>::NotProofOwner
+		);
+		assert_noop!(
+			PoeModule::revoke_claim(Origin::signed(1), vec![4, 5, 6]),
+			Error::<Test>::NoSuchProof
+		);
+	});
+}
+
+#[test]
+fn do_create_claim_records_given_owner() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+
+		assert_ok!(PoeModule::do_create_claim(42, vec![7, 7]));
+
+		assert_eq!(PoeModule::claim_of(&[7, 7]).map(|c| (c.owner, c.block)), Some((42, 3)));
+		assert_noop!(
+			PoeModule::do_create_claim(43, vec![7, 7]),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn create_claim_for_hash_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(2);
+		let hash = DocumentHash::of(HashAlgorithm::Sha2_256, b"document");
+
+		assert_ok!(PoeModule::create_claim_for_hash(Origin::signed(1), hash.algorithm, hash.digest));
+
+		assert_eq!(PoeModule::hashed_claim_of(&hash).map(|c| (c.owner, c.block)), Some((1, 2)));
+		assert_noop!(
+			PoeModule::create_claim_for_hash(Origin::signed(2), hash.algorithm, hash.digest),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn same_digest_under_different_algorithms_is_a_different_claim() {
+	new_test_ext().execute_with(|| {
+		let digest = [9u8; 32];
+
+		assert_ok!(PoeModule::create_claim_for_hash(Origin::signed(1), HashAlgorithm::Blake2_256, digest));
+		assert_ok!(PoeModule::create_claim_for_hash(Origin::signed(2), HashAlgorithm::Keccak256, digest));
+	});
+}
+
+#[test]
+fn revoke_claim_for_hash_works() {
+	new_test_ext().execute_with(|| {
+		let digest = [9u8; 32];
+		assert_ok!(PoeModule::create_claim_for_hash(Origin::signed(1), HashAlgorithm::Blake2_256, digest));
+
+		assert_noop!(
+			PoeModule::revoke_claim_for_hash(Origin::signed(2), HashAlgorithm::Blake2_256, digest),
+			Error::<Test>::NotProofOwner
+		);
+		assert_ok!(PoeModule::revoke_claim_for_hash(Origin::signed(1), HashAlgorithm::Blake2_256, digest));
+
+		let hash = DocumentHash { algorithm: HashAlgorithm::Blake2_256, digest };
+		assert_eq!(PoeModule::hashed_claim_of(&hash), None);
+	});
+}
+
+#[test]
+fn hash_document_matches_reference_digests() {
+	assert_eq!(
+		hash_document(HashAlgorithm::Blake2_256, b""),
+		hex!("0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"),
+	);
+	assert_eq!(
+		hash_document(HashAlgorithm::Sha2_256, b"abc"),
+		hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+	);
+	assert_eq!(
+		hash_document(HashAlgorithm::Keccak256, b""),
+		hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+	);
+}
+
+#[test]
+fn hash_algorithm_round_trips_through_its_name() {
+	for algorithm in &[HashAlgorithm::Blake2_256, HashAlgorithm::Sha2_256, HashAlgorithm::Keccak256] {
+		assert_eq!(algorithm.to_string().parse::<HashAlgorithm>(), Ok(*algorithm));
+	}
+	assert!("md5".parse::<HashAlgorithm>().is_err());
+}
+
+#[test]
+fn create_claim_with_metadata_works() {
+	new_test_ext().execute_with(|| {
+		let metadata = ClaimMetadata {
+			description: Some(b"ipfs://doc".to_vec()),
+			mime_type: Some(b"text/csv".to_vec()),
+			expires_at: Some(10),
+		};
+
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], metadata.clone()));
+
+		assert_eq!(PoeModule::claim_of(&[1]).map(|c| c.metadata), Some(metadata));
+	});
+}
+
+#[test]
+fn create_claim_with_metadata_enforces_bounds() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		let long_description = ClaimMetadata { description: Some(vec![0; 17]), ..Default::default() };
+		assert_noop!(
+			PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], long_description),
+			Error::<Test>::DescriptionTooLong
+		);
+
+		let long_mime_type = ClaimMetadata { mime_type: Some(vec![0; 9]), ..Default::default() };
+		assert_noop!(
+			PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], long_mime_type),
+			Error::<Test>::MimeTypeTooLong
+		);
+
+		let past_expiry = ClaimMetadata { expires_at: Some(5), ..Default::default() };
+		assert_noop!(
+			PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], past_expiry),
+			Error::<Test>::ExpiryNotInFuture
+		);
+	});
+}
+
+#[test]
+fn claims_are_removed_at_their_expiry_block() {
+	new_test_ext().execute_with(|| {
+		let expiring = ClaimMetadata { expires_at: Some(3), ..Default::default() };
+		let hash = DocumentHash::of(HashAlgorithm::Blake2_256, b"report");
+
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], expiring.clone()));
+		assert_ok!(PoeModule::create_claim_for_hash_with_metadata(
+			Origin::signed(1), hash.algorithm, hash.digest, expiring,
+		));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![2]));
+
+		PoeModule::on_initialize(2);
+		assert!(PoeModule::claim_of(&[1]).is_some());
+
+		PoeModule::on_initialize(3);
+		assert!(PoeModule::claim_of(&[1]).is_none());
+		assert!(PoeModule::hashed_claim_of(&hash).is_none());
+		assert!(PoeModule::claim_of(&[2]).is_some());
+	});
+}
+
+#[test]
+fn recreated_claim_is_not_expired_by_stale_schedule() {
+	new_test_ext().execute_with(|| {
+		let expiring = ClaimMetadata { expires_at: Some(3), ..Default::default() };
+
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], expiring));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![1]));
+		assert_ok!(PoeModule::create_claim(Origin::signed(2), vec![1]));
+
+		PoeModule::on_initialize(3);
+
+		assert_eq!(PoeModule::claim_of(&[1]).map(|c| c.owner), Some(2));
+	});
+}
+
+#[test]
+fn revoke_claim_removes_it_from_the_expiry_schedule() {
+	new_test_ext().execute_with(|| {
+		let expiring = ClaimMetadata { expires_at: Some(3), ..Default::default() };
+
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], expiring.clone()));
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![2], expiring));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![1]));
+
+		assert_eq!(ExpiringClaims::<Test>::get(3), vec![ClaimKey::Raw(vec![2])]);
+	});
+}
+
+#[test]
+fn expiry_block_is_capped() {
+	new_test_ext().execute_with(|| {
+		let expiring = ClaimMetadata { expires_at: Some(3), ..Default::default() };
+
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![1], expiring.clone()));
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![2], expiring.clone()));
+
+		assert_noop!(
+			PoeModule::create_claim_with_metadata(Origin::signed(1), vec![3], expiring.clone()),
+			Error::<Test>::ExpiryBlockFull
+		);
+
+		// Revoking frees a slot.
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![1]));
+		assert_ok!(PoeModule::create_claim_with_metadata(Origin::signed(1), vec![3], expiring));
+	});
+}
+
+#[test]
+fn runtime_upgrade_migrates_v1_claims() {
+	new_test_ext().execute_with(|| {
+		let hash = DocumentHash { algorithm: HashAlgorithm::Sha2_256, digest: [1; 32] };
+		unhashed::put(&Proofs::<Test>::hashed_key_for(vec![1]), &(7u64, 4u64));
+		unhashed::put(&HashedProofs::<Test>::hashed_key_for(&hash), &(8u64, 5u64));
+		StorageVersion::put(Releases::V1);
+
+		PoeModule::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get(), Releases::V2);
+		assert_eq!(PoeModule::claim_of(&[1]).map(|c| (c.owner, c.block)), Some((7, 4)));
+		assert_eq!(PoeModule::hashed_claim_of(&hash).map(|c| (c.owner, c.block)), Some((8, 5)));
+	});
+}
+
+#[test]
+fn runtime_upgrade_is_a_no_op_on_v2() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(StorageVersion::get(), Releases::V2);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1]));
+
+		PoeModule::on_runtime_upgrade();
+
+		assert_eq!(PoeModule::claim_of(&[1]).map(|c| c.owner), Some(1));
+	});
+}
+
+fn leaves(count: u8) -> Vec<[u8; 32]> {
+	(0..count).map(|i| hash_document(HashAlgorithm::Blake2_256, &[i])).collect()
+}
+
+#[test]
+fn merkle_proofs_verify_for_every_leaf() {
+	for count in 1..=7 {
+		let leaves = leaves(count);
+		let tree = MerkleTree::from_leaves(&leaves).unwrap();
+
+		for (index, leaf) in leaves.iter().enumerate() {
+			let proof = tree.proof(index as u32).unwrap();
+			assert!(verify_proof(&tree.root(), leaf, index as u32, count as u32, &proof));
+		}
+	}
+}
+
+#[test]
+fn merkle_proofs_reject_wrong_leaf_index_or_count() {
+	let leaves = leaves(5);
+	let tree = MerkleTree::from_leaves(&leaves).unwrap();
+	let proof = tree.proof(2).unwrap();
+
+	assert!(!verify_proof(&tree.root(), &leaves[3], 2, 5, &proof));
+	assert!(!verify_proof(&tree.root(), &leaves[2], 3, 5, &proof));
+	assert!(!verify_proof(&tree.root(), &leaves[2], 2, 6, &proof));
+	assert!(!verify_proof(&tree.root(), &leaves[2], 5, 5, &proof));
+	assert!(tree.proof(5).is_none());
+}
+
+#[test]
+fn merkle_tree_root_is_not_a_leaf() {
+	let leaf = leaves(1)[0];
+	let tree = MerkleTree::from_leaves(&[leaf]).unwrap();
+
+	assert_ne!(tree.root(), leaf);
+	assert!(MerkleTree::from_leaves(&[]).is_none());
+}
+
+#[test]
+fn create_merkle_claim_works() {
+	new_test_ext().execute_with(|| {
+		let leaves = leaves(3);
+		let tree = MerkleTree::from_leaves(&leaves).unwrap();
+		let root = tree.root();
+
+		assert_noop!(
+			PoeModule::create_merkle_claim(Origin::signed(1), root, 0),
+			Error::<Test>::EmptyMerkleTree
+		);
+		assert!(!PoeModule::verify_merkle_leaf(root, leaves[1], 1, tree.proof(1).unwrap()));
+
+		assert_ok!(PoeModule::create_merkle_claim(Origin::signed(1), root, tree.leaf_count()));
+
+		assert!(PoeModule::verify_merkle_leaf(root, leaves[1], 1, tree.proof(1).unwrap()));
+		assert_noop!(
+			PoeModule::create_merkle_claim(Origin::signed(2), root, 3),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn revoke_merkle_claim_works() {
+	new_test_ext().execute_with(|| {
+		let root = MerkleTree::from_leaves(&leaves(2)).unwrap().root();
+		assert_ok!(PoeModule::create_merkle_claim(Origin::signed(1), root, 2));
+
+		assert_noop!(
+			PoeModule::revoke_merkle_claim(Origin::signed(2), root),
+			Error::<Test>::NotProofOwner
+		);
+		assert_ok!(PoeModule::revoke_merkle_claim(Origin::signed(1), root));
+
+		assert!(PoeModule::merkle_claim_of(&root).is_none());
+	});
+}
+
+#[test]
+fn open_cosigned_claim_validates_signers() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![], 1),
+			Error::<Test>::NoSigners
+		);
+		assert_noop!(
+			PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2, 3, 4, 5], 2),
+			Error::<Test>::TooManySigners
+		);
+		assert_noop!(
+			PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2, 3], 3),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_noop!(
+			PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2, 2], 1),
+			Error::<Test>::DuplicateSigner
+		);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1]));
+		assert_noop!(
+			PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2, 3], 2),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn cosigned_claim_is_finalized_at_threshold() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2, 3, 4], 2));
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(5), vec![1]),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+		assert_noop!(PoeModule::cosign_claim(Origin::signed(5), vec![1]), Error::<Test>::NotASigner);
+
+		assert_ok!(PoeModule::cosign_claim(Origin::signed(2), vec![1]));
+		assert_noop!(PoeModule::cosign_claim(Origin::signed(2), vec![1]), Error::<Test>::AlreadyCosigned);
+		assert!(PoeModule::claim_of(&[1]).is_none());
+
+		assert_ok!(PoeModule::cosign_claim(Origin::signed(4), vec![1]));
+
+		assert_eq!(PoeModule::claim_of(&[1]).map(|c| c.owner), Some(1));
+		assert_eq!(PoeModule::cosigners(vec![1]), vec![2, 4]);
+		assert_noop!(
+			PoeModule::cosign_claim(Origin::signed(3), vec![1]),
+			Error::<Test>::NoSuchPendingClaim
+		);
+	});
+}
+
+#[test]
+fn pending_claims_expire_after_their_lifetime() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2, 3], 2));
+		assert_ok!(PoeModule::cosign_claim(Origin::signed(2), vec![1]));
+
+		PoeModule::on_initialize(10);
+		assert_ok!(PoeModule::cosign_claim(Origin::signed(3), vec![1]));
+		assert!(PoeModule::claim_of(&[1]).is_some());
+
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![2], vec![2, 3], 2));
+
+		PoeModule::on_initialize(11);
+
+		assert_noop!(
+			PoeModule::cosign_claim(Origin::signed(2), vec![2]),
+			Error::<Test>::NoSuchPendingClaim
+		);
+		assert_ok!(PoeModule::create_claim(Origin::signed(5), vec![2]));
+	});
+}
+
+#[test]
+fn pending_claims_expiring_per_block_are_capped() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![1], vec![2], 1));
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![2], vec![2], 1));
+
+		assert_noop!(
+			PoeModule::open_cosigned_claim(Origin::signed(1), vec![3], vec![2], 1),
+			Error::<Test>::ExpiryBlockFull
+		);
+
+		// Finalizing a claim frees its slot.
+		assert_ok!(PoeModule::cosign_claim(Origin::signed(2), vec![1]));
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![3], vec![2], 1));
+
+		// The next block has room again.
+		System::set_block_number(2);
+		assert_ok!(PoeModule::open_cosigned_claim(Origin::signed(1), vec![4], vec![2], 1));
+	});
+}
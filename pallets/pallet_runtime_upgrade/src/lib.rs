@@ -0,0 +1,41 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lets an origin other than root upgrade the runtime.
+//!
+//! `set_code` checks `UpgradeOrigin` and dispatches `frame_system`'s `set_code` as root, e.g. so
+//! that a council majority can upgrade the runtime without a referendum. Nothing else is
+//! dispatched as root.
+
+use frame_support::{
+	decl_module,
+	traits::{EnsureOrigin, Get},
+	weights::DispatchClass,
+};
+use frame_system::RawOrigin;
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub trait Trait: frame_system::Trait {
+	/// Origin allowed to upgrade the runtime.
+	type UpgradeOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		/// Sets the runtime code to `code`, as `system.setCode` does for root.
+		#[weight = (T::MaximumBlockWeight::get(), DispatchClass::Operational)]
+		fn set_code(origin, code: Vec<u8>) {
+			T::UpgradeOrigin::ensure_origin(origin)?;
+
+			frame_system::Module::<T>::set_code(RawOrigin::Root.into(), code)?;
+		}
+	}
+}
@@ -0,0 +1,28 @@
+use crate::mock::*;
+use frame_support::assert_noop;
+use sp_runtime::DispatchError;
+
+#[test]
+fn only_upgrade_origin_can_set_code() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			RuntimeUpgrade::set_code(Origin::signed(2), vec![1, 2, 3]),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			RuntimeUpgrade::set_code(Origin::root(), vec![1, 2, 3]),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn upgrade_origin_sets_code_as_root() {
+	new_test_ext().execute_with(|| {
+		// Getting past `ensure_root`, `frame_system` rejects the code itself.
+		assert_noop!(
+			RuntimeUpgrade::set_code(Origin::signed(1), vec![1, 2, 3]),
+			frame_system::Error::<Test>::FailedToExtractRuntimeVersion
+		);
+	});
+}
@@ -0,0 +1,155 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Manages the set of validators handed to `pallet_session`.
+//!
+//! `ManagerOrigin` adds and removes validators. The new set is handed to `pallet_session` at
+//! the next session boundary, which queues it and makes it active one session later. Added
+//! validators must have registered their session keys with `Session::set_keys` beforehand.
+
+use codec::Encode;
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, storage::migration::get_storage_value,
+	traits::{Get, EnsureOrigin}, StorageHasher, Twox64Concat,
+};
+use sp_runtime::traits::Convert;
+use sp_staking::SessionIndex;
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub trait Trait: frame_system::Trait + pallet_session::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// Origin allowed to add and remove validators.
+	type ManagerOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The number of validators that must always remain in the set.
+	type MinValidators: Get<u32>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ValidatorSet {
+		pub Validators get(fn validators) config(): Vec<T::AccountId>;
+		/// Whether `Validators` changed since it was last handed to `pallet_session`.
+		SetChanged: bool;
+	}
+	add_extra_genesis {
+		// Hand the genesis validators to `pallet_session` for the first session.
+		build(|_: &GenesisConfig<T>| SetChanged::put(true));
+	}
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId
+	{
+		/// A validator was added to the set. [who]
+		ValidatorAdded(AccountId),
+		/// A validator was removed from the set. [who]
+		ValidatorRemoved(AccountId),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account is already a validator.
+		AlreadyValidator,
+		/// The account is not a validator.
+		NotValidator,
+		/// Removing the validator would leave fewer than `MinValidators`.
+		TooFewValidators,
+		/// The account has not registered session keys with `Session::set_keys`.
+		ValidatorKeysMissing,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn add_validator(origin, who: T::AccountId) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			let mut validators = Validators::<T>::get();
+			ensure!(!validators.contains(&who), Error::<T>::AlreadyValidator);
+			ensure!(Self::has_session_keys(&who), Error::<T>::ValidatorKeysMissing);
+
+			validators.push(who.clone());
+			Validators::<T>::put(validators);
+			SetChanged::put(true);
+
+			Self::deposit_event(RawEvent::ValidatorAdded(who));
+		}
+
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 2)]
+		fn remove_validator(origin, who: T::AccountId) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			let mut validators = Validators::<T>::get();
+			let position = validators.iter().position(|v| v == &who).ok_or(Error::<T>::NotValidator)?;
+			ensure!(validators.len() as u32 > T::MinValidators::get(), Error::<T>::TooFewValidators);
+
+			validators.remove(position);
+			Validators::<T>::put(validators);
+			SetChanged::put(true);
+
+			Self::deposit_event(RawEvent::ValidatorRemoved(who));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether `who` has registered session keys, i.e. `pallet_session::NextKeys` contains the
+	/// validator id `ValidatorIdOf` maps it to.
+	///
+	/// `pallet_session` would otherwise hand default keys to Aura and GRANDPA for a validator
+	/// without any. `NextKeys` is private to `pallet_session`, so it is read by name; the
+	/// `Session` prefix comes from its `decl_storage!`, not from `construct_runtime!`.
+	pub fn has_session_keys(who: &T::AccountId) -> bool {
+		T::ValidatorIdOf::convert(who.clone()).map_or(false, |id| {
+			let hash = Twox64Concat::hash(&id.encode());
+			get_storage_value::<T::Keys>(b"Session", b"NextKeys", &hash).is_some()
+		})
+	}
+}
+
+impl<T: Trait> pallet_session::SessionManager<T::AccountId> for Module<T> {
+	fn new_session(_: SessionIndex) -> Option<Vec<T::AccountId>> {
+		if SetChanged::take() {
+			Some(Self::validators())
+		} else {
+			None
+		}
+	}
+
+	fn start_session(_: SessionIndex) {}
+
+	fn end_session(_: SessionIndex) {}
+}
+
+/// Identifies each validator by its account, for use with `pallet_session::historical`.
+impl<T: Trait> pallet_session::historical::SessionManager<T::AccountId, T::AccountId> for Module<T> {
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v.clone(), v)).collect())
+	}
+
+	fn start_session(start_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+	}
+}
@@ -0,0 +1,101 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use pallet_session::SessionManager;
+use sp_runtime::DispatchError;
+
+#[test]
+fn genesis_validators_are_handed_to_the_first_session() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::new_session(0), Some(vec![1, 2]));
+		assert_eq!(ValidatorSet::new_session(1), None);
+	});
+}
+
+#[test]
+fn add_validator_works() {
+	new_test_ext().execute_with(|| {
+		ValidatorSet::new_session(0);
+		set_session_keys(3);
+
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 3));
+
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3]);
+		assert_eq!(ValidatorSet::new_session(1), Some(vec![1, 2, 3]));
+		assert_eq!(ValidatorSet::new_session(2), None);
+	});
+}
+
+#[test]
+fn add_validator_rejects_existing_validator() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), 2),
+			Error::<Test>::AlreadyValidator
+		);
+	});
+}
+
+#[test]
+fn add_validator_requires_session_keys() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), 3),
+			Error::<Test>::ValidatorKeysMissing
+		);
+
+		set_session_keys(3);
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 3));
+	});
+}
+
+#[test]
+fn remove_validator_works() {
+	new_test_ext().execute_with(|| {
+		ValidatorSet::new_session(0);
+
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 1));
+
+		assert_eq!(ValidatorSet::validators(), vec![2]);
+		assert_eq!(ValidatorSet::new_session(1), Some(vec![2]));
+	});
+}
+
+#[test]
+fn remove_validator_rejects_unknown_account() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 3),
+			Error::<Test>::NotValidator
+		);
+	});
+}
+
+#[test]
+fn remove_validator_keeps_min_validators() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 1));
+
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 2),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn only_manager_can_change_validators() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 3), DispatchError::BadOrigin);
+		assert_noop!(ValidatorSet::remove_validator(Origin::signed(1), 2), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn historical_session_manager_identifies_validators_by_account() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			<ValidatorSet as pallet_session::historical::SessionManager<u64, u64>>::new_session(0),
+			Some(vec![(1, 1), (2, 2)])
+		);
+	});
+}
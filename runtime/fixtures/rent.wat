@@ -0,0 +1,40 @@
+;; Stores a value when deployed, so that the contract pays rent for it. When called with a
+;; destination, a code hash and a rent allowance as input, restores the destination's tombstone
+;; from this contract's storage.
+(module
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_restore_to"
+		(func $seal_restore_to (param i32 i32 i32 i32 i32 i32 i32 i32))
+	)
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) storage key
+	(data (i32.const 0) "\01")
+
+	;; [32, 36) storage value
+	(data (i32.const 32) "\28\00\00\00")
+
+	;; [36, 40) size of the input buffer
+	(data (i32.const 36) "\50")
+
+	;; [40, 120) input: destination (32 bytes), code hash (32 bytes), rent allowance (16 bytes)
+
+	(func (export "deploy")
+		(call $seal_set_storage (i32.const 0) (i32.const 32) (i32.const 4))
+	)
+
+	(func (export "call")
+		(call $seal_input (i32.const 40) (i32.const 36))
+		(call $seal_restore_to
+			;; Destination
+			(i32.const 40) (i32.const 32)
+			;; Code hash
+			(i32.const 72) (i32.const 32)
+			;; Rent allowance
+			(i32.const 104) (i32.const 16)
+			;; No storage keys to remove
+			(i32.const 0) (i32.const 0)
+		)
+	)
+)
@@ -0,0 +1,582 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::{DispatchError, DispatchResult},
+	traits::{
+		Contains, ContainsLengthBound, Currency, ExistenceRequirement, Get, Imbalance, InstanceFilter,
+		OnUnbalanced,
+	},
+	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
+};
+use pallet_session::historical::IdentificationTuple;
+use smallvec::smallvec;
+use sp_runtime::RuntimeDebug;
+use sp_staking::{SessionIndex, offence::{OffenceDetails, OnOffenceHandler}};
+use sp_std::prelude::*;
+use crate::{
+	AccountId, Assets, Authorship, Balance, Balances, CENTS, Call, Council, CouncilMaxMembers,
+	AssetId, CurrencyId, ExtrinsicBaseWeight, Origin, Perbill, Runtime, Session, Treasury, Weight,
+};
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Pays the author of the current block, or the treasury for blocks sealed without an author,
+/// e.g. with `--manual-seal`.
+pub struct Author;
+
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		let author = Authorship::author();
+		if author == AccountId::default() {
+			Treasury::on_unbalanced(amount);
+		} else {
+			Balances::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees 80% to the treasury and 20% to the block author. Tips go to the
+/// block author in full.
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let (to_treasury, mut to_author) = fees.ration(80, 20);
+			if let Some(tips) = fees_then_tips.next() {
+				to_author.subsume(tips);
+			}
+			Treasury::on_unbalanced(to_treasury);
+			Author::on_unbalanced(to_author);
+		}
+	}
+}
+
+/// Council members act as treasury tippers.
+pub struct CouncilTippers;
+
+impl Contains<AccountId> for CouncilTippers {
+	fn sorted_members() -> Vec<AccountId> {
+		// `pallet_membership` keeps the council sorted.
+		Council::members()
+	}
+}
+
+impl ContainsLengthBound for CouncilTippers {
+	fn min_len() -> usize {
+		0
+	}
+
+	fn max_len() -> usize {
+		CouncilMaxMembers::get() as usize
+	}
+}
+
+/// Kitty payments in the native token or in `pallet_assets` tokens.
+pub struct NativeOrAssets;
+
+impl pallet_kitties::MultiCurrency<AccountId> for NativeOrAssets {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+
+	fn free_balance(currency_id: CurrencyId, who: &AccountId) -> Balance {
+		match currency_id {
+			CurrencyId::Native => Balances::free_balance(who),
+			CurrencyId::Asset(id) => Assets::balance(id, who.clone()),
+		}
+	}
+
+	fn transfer(
+		currency_id: CurrencyId,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		match currency_id {
+			CurrencyId::Native =>
+				<Balances as Currency<_>>::transfer(from, to, amount, existence_requirement),
+			// `pallet_assets` has no existential deposit and holds no account references, so an
+			// asset balance can be emptied without reaping `from` and `existence_requirement`
+			// has nothing to protect there.
+			CurrencyId::Asset(id) => Assets::transfer(Origin::signed(from.clone()), id, to.clone().into(), amount),
+		}
+	}
+}
+
+/// Mints the shares of fractionalized kitties as new `Assets` tokens.
+pub struct AssetShares;
+
+impl pallet_kitties::Fungibles<AccountId> for AssetShares {
+	type AssetId = AssetId;
+	type Balance = Balance;
+
+	fn issue(who: &AccountId, amount: Balance) -> Result<AssetId, DispatchError> {
+		let id = Assets::next_asset_id();
+		Assets::issue(Origin::signed(who.clone()), amount)?;
+		Ok(id)
+	}
+
+	fn balance(id: AssetId, who: &AccountId) -> Balance {
+		Assets::balance(id, who.clone())
+	}
+
+	fn total_supply(id: AssetId) -> Balance {
+		Assets::total_supply(id)
+	}
+
+	fn burn_all(id: AssetId, who: &AccountId) -> DispatchResult {
+		Assets::destroy(Origin::signed(who.clone()), id)
+	}
+}
+
+/// The calls a proxy account may make on behalf of its delegator.
+///
+/// Restricted proxies may also use `Utility` batches; the calls inside a batch are filtered
+/// just the same.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+	/// Any call.
+	Any,
+	/// Only calls to the kitties pallet.
+	Kitties,
+	/// Only calls to the proof of existence pallet.
+	Poe,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::Kitties => matches!(c, Call::KittiesModule(..) | Call::Utility(..)),
+			ProxyType::Poe => matches!(c, Call::PoeModule(..) | Call::Utility(..)),
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Converts a dispatch weight into a fee.
+///
+/// The linear term charges `CENTS / 10` for an `ExtrinsicBaseWeight`. The quadratic term is
+/// negligible for ordinary extrinsics but makes block-filling ones disproportionately expensive:
+/// an extrinsic of `MaximumExtrinsicWeight` pays about 10 `DOLLARS` linearly and 17 `DOLLARS`
+/// quadratically.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let p = CENTS / 10;
+		let q = Balance::from(ExtrinsicBaseWeight::get());
+		smallvec![
+			WeightToFeeCoefficient {
+				degree: 1,
+				negative: false,
+				coeff_frac: Perbill::from_rational_approximation(p % q, q),
+				coeff_integer: p / q,
+			},
+			WeightToFeeCoefficient {
+				degree: 2,
+				negative: false,
+				coeff_frac: Perbill::from_parts(1),
+				coeff_integer: 0,
+			},
+		]
+	}
+}
+
+/// Punishes reported offenders by disabling them for the rest of the session and slashing
+/// the reported fraction of their free balance to the treasury.
+pub struct DisableAndSlash;
+
+impl OnOffenceHandler<AccountId, IdentificationTuple<Runtime>, Weight> for DisableAndSlash {
+	fn on_offence(
+		offenders: &[OffenceDetails<AccountId, IdentificationTuple<Runtime>>],
+		slash_fraction: &[Perbill],
+		_session: SessionIndex,
+	) -> Result<Weight, ()> {
+		let db_weight = <Runtime as frame_system::Trait>::DbWeight::get();
+		let mut weight = 0;
+
+		for (details, fraction) in offenders.iter().zip(slash_fraction) {
+			let (validator, _) = &details.offender;
+
+			// `disable` only fails for validators that already left the current set. They no
+			// longer author or vote, so there is nothing to disable, but they are still slashed.
+			let _ = Session::disable(validator);
+
+			let slash = *fraction * Balances::free_balance(validator);
+			let (slashed, _) = Balances::slash(validator, slash);
+			Treasury::on_unbalanced(slashed);
+
+			weight += db_weight.reads_writes(3, 3);
+		}
+
+		Ok(weight)
+	}
+
+	fn can_report() -> bool {
+		true
+	}
+}
+
+#[cfg(test)]
+mod multiplier_tests {
+	use super::*;
+	use pallet_transaction_payment::Multiplier;
+	use sp_runtime::{FixedPointNumber, traits::Convert};
+	use crate::{
+		AvailableBlockRatio, DOLLARS, MaximumBlockWeight, MaximumExtrinsicWeight, MinimumMultiplier,
+		System, TargetBlockFullness,
+	};
+
+	fn max_normal() -> Weight {
+		AvailableBlockRatio::get() * MaximumBlockWeight::get()
+	}
+
+	fn target() -> Weight {
+		TargetBlockFullness::get() * max_normal()
+	}
+
+	fn runtime_multiplier_update(fm: Multiplier) -> Multiplier {
+		<Runtime as pallet_transaction_payment::Trait>::FeeMultiplierUpdate::convert(fm)
+	}
+
+	fn run_with_system_weight<F>(w: Weight, assertions: F) where F: Fn() -> () {
+		let mut t: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>().unwrap().into();
+		t.execute_with(|| {
+			System::set_block_limits(w, 0);
+			assertions()
+		});
+	}
+
+	#[test]
+	fn multiplier_is_stable_at_target_fullness() {
+		run_with_system_weight(target(), || {
+			let fm = Multiplier::saturating_from_rational(3, 2);
+			assert_eq!(runtime_multiplier_update(fm), fm);
+		});
+	}
+
+	#[test]
+	fn multiplier_grows_on_consecutive_full_blocks() {
+		run_with_system_weight(max_normal(), || {
+			let mut fm = Multiplier::one();
+			for _ in 0..1_000 {
+				let next = runtime_multiplier_update(fm);
+				assert!(next > fm);
+				fm = next;
+			}
+			// A thousand full blocks, under two hours, make fees noticeably more expensive.
+			assert!(fm > Multiplier::saturating_from_rational(1001, 1000));
+		});
+	}
+
+	#[test]
+	fn multiplier_shrinks_on_consecutive_empty_blocks() {
+		run_with_system_weight(0, || {
+			let mut fm = Multiplier::one();
+			for _ in 0..1_000 {
+				let next = runtime_multiplier_update(fm);
+				assert!(next < fm);
+				fm = next;
+			}
+		});
+	}
+
+	#[test]
+	fn multiplier_never_drops_below_minimum() {
+		run_with_system_weight(0, || {
+			let fm = runtime_multiplier_update(MinimumMultiplier::get());
+			assert_eq!(fm, MinimumMultiplier::get());
+		});
+	}
+
+	#[test]
+	fn multiplier_recovers_after_full_blocks() {
+		let mut fm = Multiplier::one();
+		run_with_system_weight(max_normal(), || {
+			for _ in 0..100 {
+				fm = runtime_multiplier_update(fm);
+			}
+		});
+		let peak = fm;
+		assert!(peak > Multiplier::one());
+
+		run_with_system_weight(0, || {
+			for _ in 0..100 {
+				fm = runtime_multiplier_update(fm);
+			}
+		});
+		assert!(fm < peak);
+	}
+
+	#[test]
+	fn block_weight_is_capped_at_normal_maximum() {
+		let mut capped = Multiplier::one();
+		let mut overweight = Multiplier::one();
+		run_with_system_weight(max_normal(), || capped = runtime_multiplier_update(capped));
+		run_with_system_weight(MaximumBlockWeight::get(), || overweight = runtime_multiplier_update(overweight));
+		assert_eq!(capped, overweight);
+	}
+
+	#[test]
+	fn weight_to_fee_is_calibrated_against_cents() {
+		assert_eq!(WeightToFee::calc(&0), 0);
+		// The quadratic term adds `125_000_000^2 / 10^9` planck, under a thousandth of a cent,
+		// for the base weight.
+		let base = WeightToFee::calc(&ExtrinsicBaseWeight::get());
+		assert_eq!(base, CENTS / 10 + 15_625_000);
+	}
+
+	#[test]
+	fn weight_to_fee_is_super_linear() {
+		let max = MaximumExtrinsicWeight::get();
+		let half = WeightToFee::calc(&(max / 2));
+		let full = WeightToFee::calc(&max);
+
+		assert!(full > 2 * half);
+		assert!(full > 20 * DOLLARS && full < 30 * DOLLARS);
+	}
+}
+
+#[cfg(test)]
+mod fee_tests {
+	use super::*;
+	use codec::Encode;
+	use sp_consensus_aura::{AURA_ENGINE_ID, sr25519::AuthorityId as AuraId};
+	use sp_core::{ed25519, sr25519};
+	use sp_runtime::{BuildStorage, DigestItem};
+	use pallet_grandpa::AuthorityId as GrandpaId;
+	use crate::{opaque::SessionKeys, DOLLARS, System};
+
+	fn author() -> AccountId {
+		AccountId::from([7; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_session::GenesisConfig::<Runtime> {
+			keys: vec![(author(), author(), SessionKeys {
+				aura: AuraId::from(sr25519::Public::from_raw([1; 32])),
+				grandpa: GrandpaId::from(ed25519::Public::from_raw([1; 32])),
+			})],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn pay_fees(fee: Balance, tip: Balance) {
+		DealWithFees::on_unbalanceds(vec![Balances::issue(fee), Balances::issue(tip)].into_iter());
+	}
+
+	#[test]
+	fn fees_are_split_between_treasury_and_author() {
+		new_test_ext().execute_with(|| {
+			// Aura slot 0, authored by the only validator.
+			System::deposit_log(DigestItem::PreRuntime(AURA_ENGINE_ID, 0u64.encode()));
+
+			pay_fees(10 * CENTS, 1 * CENTS);
+
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 8 * CENTS);
+			assert_eq!(Balances::free_balance(author()), 3 * CENTS);
+		});
+	}
+
+	#[test]
+	fn fees_go_to_treasury_without_author() {
+		new_test_ext().execute_with(|| {
+			pay_fees(10 * CENTS, 1 * CENTS);
+
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 11 * CENTS);
+			assert_eq!(Balances::free_balance(author()), 0);
+		});
+	}
+
+	#[test]
+	fn offence_slashes_go_to_treasury() {
+		new_test_ext().execute_with(|| {
+			Balances::make_free_balance_be(&author(), 10 * DOLLARS);
+
+			let offender = OffenceDetails { offender: (author(), author()), reporters: vec![] };
+			assert!(DisableAndSlash::on_offence(&[offender], &[Perbill::from_percent(10)], 0).is_ok());
+
+			assert_eq!(Balances::free_balance(author()), 9 * DOLLARS);
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 1 * DOLLARS);
+			assert_eq!(Session::disabled_validators(), vec![0]);
+		});
+	}
+}
+
+#[cfg(test)]
+mod proxy_tests {
+	use super::*;
+	use frame_support::{assert_ok, dispatch::DispatchError};
+	use sp_runtime::BuildStorage;
+	use crate::{DOLLARS, Event, KittiesModule, Origin, Proxy, System};
+
+	fn alice() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn bot() -> AccountId {
+		AccountId::from([2; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(alice(), 10 * DOLLARS), (bot(), 10 * DOLLARS)],
+		}.assimilate_storage(&mut t).unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn last_event() -> Event {
+		System::events().pop().expect("an event was deposited").event
+	}
+
+	fn create_kitty() -> Call {
+		Call::KittiesModule(pallet_kitties::Call::create_kitty())
+	}
+
+	fn transfer() -> Call {
+		Call::Balances(pallet_balances::Call::transfer(bot().into(), 1 * DOLLARS))
+	}
+
+	#[test]
+	fn restricted_proxies_only_pass_their_pallet() {
+		assert!(ProxyType::Any.filter(&transfer()));
+		assert!(ProxyType::Kitties.filter(&create_kitty()));
+		assert!(!ProxyType::Kitties.filter(&transfer()));
+		assert!(!ProxyType::Poe.filter(&create_kitty()));
+	}
+
+	#[test]
+	fn any_is_superset_of_restricted_proxies() {
+		assert!(ProxyType::Any.is_superset(&ProxyType::Kitties));
+		assert!(ProxyType::Kitties.is_superset(&ProxyType::Kitties));
+		assert!(!ProxyType::Kitties.is_superset(&ProxyType::Any));
+		assert!(!ProxyType::Kitties.is_superset(&ProxyType::Poe));
+	}
+
+	#[test]
+	fn kitties_proxy_cannot_transfer_funds() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Proxy::add_proxy(Origin::signed(alice()), bot(), ProxyType::Kitties, 0));
+
+			assert_ok!(Proxy::proxy(Origin::signed(bot()), alice(), None, Box::new(create_kitty())));
+			assert_eq!(KittiesModule::next_kitty_id(), 1);
+
+			assert_ok!(Proxy::proxy(Origin::signed(bot()), alice(), None, Box::new(transfer())));
+			assert_eq!(
+				last_event(),
+				Event::pallet_proxy(pallet_proxy::RawEvent::ProxyExecuted(Err(DispatchError::BadOrigin))),
+			);
+		});
+	}
+
+	#[test]
+	fn batched_calls_are_filtered() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Proxy::add_proxy(Origin::signed(alice()), bot(), ProxyType::Kitties, 0));
+
+			let batch = Call::Utility(pallet_utility::Call::batch(vec![
+				create_kitty(),
+				transfer(),
+				create_kitty(),
+			]));
+			assert_ok!(Proxy::proxy(Origin::signed(bot()), alice(), None, Box::new(batch)));
+
+			// The batch stops at the transfer.
+			assert_eq!(KittiesModule::next_kitty_id(), 1);
+			assert_eq!(Balances::free_balance(bot()), 10 * DOLLARS);
+		});
+	}
+}
+
+#[cfg(test)]
+mod currency_tests {
+	use super::*;
+	use frame_support::assert_ok;
+	use pallet_kitties::{Fungibles, MultiCurrency};
+	use sp_runtime::BuildStorage;
+	use crate::DOLLARS;
+
+	fn alice() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::from([2; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(alice(), 10 * DOLLARS), (bob(), 10 * DOLLARS)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	#[test]
+	fn native_transfers_use_balances() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NativeOrAssets::transfer(
+				CurrencyId::Native,
+				&alice(),
+				&bob(),
+				1 * DOLLARS,
+				ExistenceRequirement::KeepAlive,
+			));
+
+			assert_eq!(NativeOrAssets::free_balance(CurrencyId::Native, &alice()), 9 * DOLLARS);
+			assert_eq!(NativeOrAssets::free_balance(CurrencyId::Native, &bob()), 11 * DOLLARS);
+		});
+	}
+
+	#[test]
+	fn asset_transfers_use_assets() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(alice()), 1_000));
+			let token = CurrencyId::Asset(0);
+
+			assert_ok!(NativeOrAssets::transfer(token, &alice(), &bob(), 400, ExistenceRequirement::KeepAlive));
+
+			assert_eq!(NativeOrAssets::free_balance(token, &alice()), 600);
+			assert_eq!(NativeOrAssets::free_balance(token, &bob()), 400);
+			assert_eq!(Balances::free_balance(alice()), 10 * DOLLARS);
+		});
+	}
+	#[test]
+	fn shares_are_new_assets() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(alice()), 1_000));
+
+			let shares = AssetShares::issue(&bob(), 100).unwrap();
+			assert_eq!(shares, 1);
+			assert_eq!(AssetShares::balance(shares, &bob()), 100);
+			assert_eq!(AssetShares::total_supply(shares), 100);
+
+			assert_ok!(AssetShares::burn_all(shares, &bob()));
+			assert_eq!(AssetShares::balance(shares, &bob()), 0);
+			assert_eq!(AssetShares::total_supply(shares), 0);
+		});
+	}
+}
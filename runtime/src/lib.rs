@@ -7,18 +7,25 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use sp_std::prelude::*;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use codec::{Decode, Encode};
+use sp_core::{
+	crypto::KeyTypeId, OpaqueMetadata,
+	u32_trait::{_1, _2, _3, _4},
+};
+use frame_system::{EnsureOneOf, EnsureRoot};
 use sp_runtime::{
 	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Saturating,
+	BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Saturating, OpaqueKeys,
+	ConvertInto,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_grandpa::fg_primitives;
+use pallet_session::historical as pallet_session_historical;
 use sp_version::RuntimeVersion;
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
@@ -30,7 +37,8 @@ use pallet_contracts_rpc_runtime_api::ContractExecResult;
 pub use sp_runtime::BuildStorage;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_balances::Call as BalancesCall;
-pub use sp_runtime::{Permill, Perbill};
+pub use sp_runtime::{Permill, Perbill, Perquintill, Percent, FixedPointNumber, ModuleId};
+use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
 	traits::{KeyOwnerProofSystem, Randomness},
@@ -60,6 +68,18 @@ pub type AccountIndex = u32;
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of a `pallet_assets` token.
+pub type AssetId = u32;
+
+/// A currency kitties can be sold for.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub enum CurrencyId {
+	/// The native token, held in `pallet_balances`.
+	Native,
+	/// A token of `pallet_assets`.
+	Asset(AssetId),
+}
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -97,10 +117,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 301,
+	spec_version: 328,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 4,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -140,14 +160,14 @@ parameter_types! {
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Trait for Runtime {
-	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = ();
+	/// The basic call filter to use in dispatchable. Rejects calls paused by the circuit breaker.
+	type BaseCallFilter = CircuitBreaker;
 	/// The identifier used to distinguish between accounts.
 	type AccountId = AccountId;
 	/// The aggregated dispatch type that is available for extrinsics.
 	type Call = Call;
 	/// The lookup mechanism to get account ID from whatever is passed in dispatchers.
-	type Lookup = multiaddress::AccountIdLookup<AccountId, ()>;
+	type Lookup = multiaddress::AccountIdLookup<AccountId, AccountIndex, Runtime>;
 	/// The index type for storing how many extrinsics an account has signed.
 	type Index = Index;
 	/// The index type for blocks.
@@ -206,7 +226,7 @@ impl pallet_grandpa::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
 
-	type KeyOwnerProofSystem = ();
+	type KeyOwnerProofSystem = Historical;
 
 	type KeyOwnerProof =
 		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -216,15 +236,78 @@ impl pallet_grandpa::Trait for Runtime {
 		GrandpaId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation = ();
+	type HandleEquivocation = pallet_grandpa::EquivocationHandler<Self::KeyOwnerIdentification, Offences>;
 
 	type WeightInfo = ();
 }
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime where
+	Call: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
+}
+
+parameter_types! {
+	pub const MinValidators: u32 = 1;
+}
+
+impl pallet_validator_set::Trait for Runtime {
+	type Event = Event;
+	type ManagerOrigin = EnsureRootOrHalfCouncil;
+	type MinValidators = MinValidators;
+}
+
+parameter_types! {
+	pub const SessionPeriod: BlockNumber = 1 * HOURS;
+	pub const SessionOffset: BlockNumber = 0;
+	pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(33);
+}
+
+impl pallet_session::Trait for Runtime {
+	type Event = Event;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+	type WeightInfo = ();
+}
+
+impl pallet_session::historical::Trait for Runtime {
+	type FullIdentification = AccountId;
+	type FullIdentificationOf = ConvertInto;
+}
+
+parameter_types! {
+	pub OffencesWeightSoftLimit: Weight = Perbill::from_percent(60) * MaximumBlockWeight::get();
+}
+
+impl pallet_offences::Trait for Runtime {
+	type Event = Event;
+	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = impls::DisableAndSlash;
+	type WeightSoftLimit = OffencesWeightSoftLimit;
+}
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = SLOT_DURATION / 2;
 }
 
+parameter_types! {
+	pub const UncleGenerations: BlockNumber = 0;
+}
+
+impl pallet_authorship::Trait for Runtime {
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
 impl pallet_timestamp::Trait for Runtime {
 	/// A timestamp: milliseconds since the unix epoch.
 	type Moment = u64;
@@ -252,7 +335,7 @@ impl pallet_contracts::Trait for Runtime {
     type Event = Event;
     type DetermineContractAddress = pallet_contracts::SimpleAddressDeterminer<Runtime>;
     type TrieIdGenerator = pallet_contracts::TrieIdFromParentCounter<Runtime>;
-    type RentPayment = ();
+    type RentPayment = Treasury;
     type SignedClaimHandicap = pallet_contracts::DefaultSignedClaimHandicap;
     type TombstoneDeposit = TombstoneDeposit;
     type StorageSizeOffset = pallet_contracts::DefaultStorageSizeOffset;
@@ -264,6 +347,75 @@ impl pallet_contracts::Trait for Runtime {
     type WeightPrice = pallet_transaction_payment::Module<Self>;
 }
 
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = 1 * DOLLARS;
+	pub const SpendPeriod: BlockNumber = 1 * DAYS;
+	/// Unspent funds stay in the pot for future bounties.
+	pub const Burn: Permill = Permill::from_percent(0);
+	pub const TipCountdown: BlockNumber = 1 * DAYS;
+	pub const TipFindersFee: Percent = Percent::from_percent(20);
+	pub const TipReportDepositBase: Balance = 1 * DOLLARS;
+	pub const DataDepositPerByte: Balance = 1 * CENTS;
+	pub const BountyDepositBase: Balance = 1 * DOLLARS;
+	pub const BountyDepositPayoutDelay: BlockNumber = 1 * DAYS;
+	pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+	pub const BountyUpdatePeriod: BlockNumber = 14 * DAYS;
+	pub const MaximumReasonLength: u32 = 16384;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: Balance = 5 * DOLLARS;
+}
+
+impl pallet_treasury::Trait for Runtime {
+	type ModuleId = TreasuryModuleId;
+	type Currency = Balances;
+	type ApproveOrigin = EnsureRootOrHalfCouncil;
+	type RejectOrigin = EnsureRootOrHalfCouncil;
+	type Tippers = impls::CouncilTippers;
+	type TipCountdown = TipCountdown;
+	type TipFindersFee = TipFindersFee;
+	type TipReportDepositBase = TipReportDepositBase;
+	type DataDepositPerByte = DataDepositPerByte;
+	type Event = Event;
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
+	type MaximumReasonLength = MaximumReasonLength;
+	type BurnDestination = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const IndexDeposit: Balance = 1 * DOLLARS;
+}
+
+impl pallet_indices::Trait for Runtime {
+	type AccountIndex = AccountIndex;
+	type Currency = Balances;
+	type Deposit = IndexDeposit;
+	type Event = Event;
+	type WeightInfo = ();
+}
+
+/// Resolves `MultiAddress::Index` through `pallet_indices` and `MultiAddress::Address20`
+/// through `pallet_eth_accounts`.
+impl multiaddress::AddressResolver<AccountId, AccountIndex> for Runtime {
+	fn resolve_index(index: AccountIndex) -> Option<AccountId> {
+		Indices::lookup_index(index)
+	}
+
+	fn resolve_address20(address: [u8; 20]) -> Option<AccountId> {
+		EthAccounts::lookup_address(&address)
+	}
+}
+
 impl pallet_balances::Trait for Runtime {
 	type MaxLocks = MaxLocks;
 	/// The type for recording an account's balance.
@@ -277,15 +429,202 @@ impl pallet_balances::Trait for Runtime {
 }
 
 parameter_types! {
-	pub const TransactionByteFee: Balance = 1;
+	pub const TransactionByteFee: Balance = 10 * MILLICENTS;
+	/// Fees rise while blocks are fuller than this, and fall while they are emptier.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(1, 100_000);
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000_000u128);
 }
 
 impl pallet_transaction_payment::Trait for Runtime {
 	type Currency = Balances;
-	type OnTransactionPayment = ();
+	type OnTransactionPayment = impls::DealWithFees;
 	type TransactionByteFee = TransactionByteFee;
-	type WeightToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ();
+	type WeightToFee = impls::WeightToFee;
+	type FeeMultiplierUpdate =
+		TargetedFeeAdjustment<Self, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	/// Upper bound on the council size assumed by treasury tip weights.
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Trait<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type WeightInfo = ();
+}
+
+/// Root, or more than half of the council. Replaces `EnsureRoot` for day-to-day
+/// administration, so that sudo can be removed without losing access.
+type EnsureRootOrHalfCouncil = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>
+>;
+
+impl pallet_membership::Trait<pallet_membership::Instance1> for Runtime {
+	type Event = Event;
+	type AddOrigin = EnsureRootOrHalfCouncil;
+	type RemoveOrigin = EnsureRootOrHalfCouncil;
+	type SwapOrigin = EnsureRootOrHalfCouncil;
+	type ResetOrigin = EnsureRootOrHalfCouncil;
+	type PrimeOrigin = EnsureRootOrHalfCouncil;
+	type MembershipInitialized = Council;
+	type MembershipChanged = Council;
+}
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+	pub const VotingPeriod: BlockNumber = 7 * DAYS;
+	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+	pub const InstantAllowed: bool = true;
+	pub const MinimumDeposit: Balance = 100 * DOLLARS;
+	pub const EnactmentPeriod: BlockNumber = 1 * DAYS;
+	pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+	pub const MaxVotes: u32 = 100;
+}
+
+/// Referenda are enacted as root, so this is how root-only calls are made without sudo: a
+/// council majority proposes a majority-carries referendum and fast-tracks it. Runtime upgrades
+/// can also be made by a council majority directly, through `RuntimeUpgrade`.
+impl pallet_democracy::Trait for Runtime {
+	type Proposal = Call;
+	type Event = Event;
+	type Currency = Balances;
+	type EnactmentPeriod = EnactmentPeriod;
+	type LaunchPeriod = LaunchPeriod;
+	type VotingPeriod = VotingPeriod;
+	type MinimumDeposit = MinimumDeposit;
+	/// More than half of the council can propose the next referendum.
+	type ExternalOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+	/// More than half of the council can have the next referendum be a simple-majority vote.
+	type ExternalMajorityOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+	/// A unanimous council can have the next referendum be a negative-turnout-bias vote.
+	type ExternalDefaultOrigin = pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	/// More than half of the council can fast-track an external majority proposal.
+	type FastTrackOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+	/// A unanimous council can enact an external proposal immediately.
+	type InstantOrigin = pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	type InstantAllowed = InstantAllowed;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	/// Three quarters of the council can cancel a referendum.
+	type CancellationOrigin = pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
+	/// Any single council member may veto a coming council proposal, however they can
+	/// only do it once and it lasts only for the cooloff period.
+	type VetoOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+	type CooloffPeriod = CooloffPeriod;
+	type PreimageByteDeposit = PreimageByteDeposit;
+	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+	type Slash = Treasury;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type MaxVotes = MaxVotes;
+	type WeightInfo = ();
+}
+
+/// Lets a council majority upgrade the runtime without a referendum.
+impl pallet_runtime_upgrade::Trait for Runtime {
+	type UpgradeOrigin = EnsureRootOrHalfCouncil;
+}
+
+impl pallet_utility::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const DepositBase: Balance = 30 * CENTS + 88 * MILLICENTS;
+	// Additional storage item size of 32 bytes.
+	pub const DepositFactor: Balance = 32 * MILLICENTS;
+	pub const MaxSignatories: u16 = 100;
+}
+
+impl pallet_multisig::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
+	type MaxSignatories = MaxSignatories;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = 30 * CENTS + 40 * MILLICENTS;
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = 33 * MILLICENTS;
+	pub const MaxProxies: u16 = 32;
+	pub const AnnouncementDepositBase: Balance = 30 * CENTS + 8 * MILLICENTS;
+	pub const AnnouncementDepositFactor: Balance = 66 * MILLICENTS;
+	pub const MaxPending: u16 = 32;
+}
+
+impl pallet_proxy::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = impls::ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+	type WeightInfo = ();
+	type MaxPending = MaxPending;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+parameter_types! {
+	pub const MinVestedTransfer: Balance = 1 * DOLLARS;
+}
+
+impl pallet_vesting::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+	type WeightInfo = ();
+}
+
+/// Vested transfers from any account, e.g. the treasury, by root or a council majority.
+impl pallet_distribution::Trait for Runtime {
+	type Event = Event;
+	type Vesting = Vesting;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type MinVestedTransfer = MinVestedTransfer;
+}
+
+parameter_types! {
+	/// `Timestamp` provides an inherent, without which no block can be built. The governance
+	/// pallets stay callable so that a council majority can always resume what it paused.
+	pub UnpausablePallets: Vec<Vec<u8>> = vec![
+		b"System".to_vec(),
+		b"Timestamp".to_vec(),
+		b"Sudo".to_vec(),
+		b"CircuitBreaker".to_vec(),
+		b"Council".to_vec(),
+		b"CouncilMembership".to_vec(),
+		b"Democracy".to_vec(),
+		b"RuntimeUpgrade".to_vec(),
+	];
+}
+
+impl pallet_circuit_breaker::Trait for Runtime {
+	type Event = Event;
+	type PauseOrigin = EnsureRootOrHalfCouncil;
+	type UnpausablePallets = UnpausablePallets;
 }
 
 impl pallet_sudo::Trait for Runtime {
@@ -298,14 +637,52 @@ impl pallet_template::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const MaxClaimDescriptionLength: u32 = 256;
+	pub const MaxClaimMimeTypeLength: u32 = 64;
+	pub const MaxClaimsExpiringPerBlock: u32 = 64;
+	pub const MaxClaimSigners: u32 = 16;
+	pub const PendingClaimLifetime: BlockNumber = 7 * DAYS;
+}
+
 impl pallet_poe::Trait for Runtime {
 	type Event = Event;
+	type MaxDescriptionLength = MaxClaimDescriptionLength;
+	type MaxMimeTypeLength = MaxClaimMimeTypeLength;
+	type MaxExpiringPerBlock = MaxClaimsExpiringPerBlock;
+	type MaxSigners = MaxClaimSigners;
+	type PendingClaimLifetime = PendingClaimLifetime;
+}
+
+parameter_types! {
+	pub const EthAccountsPrefix: &'static [u8] = b"Link to node-template account:";
+}
+
+impl pallet_eth_accounts::Trait for Runtime {
+	type Event = Event;
+	type Prefix = EthAccountsPrefix;
+}
+
+impl pallet_assets::Trait for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+}
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = CurrencyId::Native;
+	pub const KittiesModuleId: ModuleId = ModuleId(*b"py/kitty");
+	pub const BuyoutPeriod: BlockNumber = 3 * DAYS;
 }
 
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
 	type RandomnessSource = RandomnessCollectiveFlip;
-	type Currency = Balances;
+	type Currency = impls::NativeOrAssets;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type Shares = impls::AssetShares;
+	type ModuleId = KittiesModuleId;
+	type BuyoutPeriod = BuyoutPeriod;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -319,25 +696,61 @@ construct_runtime!(
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
 		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
 		Aura: pallet_aura::{Module, Config<T>, Inherent},
-		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
+		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event, ValidateUnsigned},
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
 
-		Nicks: pallet_nicks::{Module, Call, Storage, Event<T>},
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
 		Contracts: pallet_contracts::{Module, Call, Config, Storage, Event<T>},
 
-		PoeModule: pallet_poe::{Module, Call, Storage, Event<T>},
+		PoeModule: pallet_poe::{Module, Call, Config, Storage, Event<T>},
 		KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>},
+
+		Indices: pallet_indices::{Module, Call, Storage, Config<T>, Event<T>},
+		EthAccounts: pallet_eth_accounts::{Module, Call, Storage, Event<T>},
+
+		// Must come before `Session`, whose genesis asks it for the initial validators.
+		ValidatorSet: pallet_validator_set::{Module, Call, Storage, Config<T>, Event<T>},
+		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
+		Historical: pallet_session_historical::{Module},
+		Offences: pallet_offences::{Module, Call, Storage, Event},
+
+		Authorship: pallet_authorship::{Module, Call, Storage},
+		Treasury: pallet_treasury::{Module, Call, Storage, Config, Event<T>},
+
+		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		CouncilMembership: pallet_membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
+		Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
+
+		Utility: pallet_utility::{Module, Call, Event},
+		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+		Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
+
+		CircuitBreaker: pallet_circuit_breaker::{Module, Call, Storage, Event},
+
+		Identity: pallet_identity::{Module, Call, Storage, Event<T>},
+
+		Vesting: pallet_vesting::{Module, Call, Storage, Event<T>, Config<T>},
+		Distribution: pallet_distribution::{Module, Call, Event<T>},
+
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+
+		RuntimeUpgrade: pallet_runtime_upgrade::{Module, Call},
 	}
 );
 
+/// Implementations of some helper traits passed into runtime modules as associated types.
+mod impls;
+
+/// Storage migrations run on runtime upgrade.
+mod migrations;
+
 /// The address format for describing accounts.
 mod multiaddress;
-pub type Address = multiaddress::MultiAddress<AccountId, ()>;
+pub type Address = multiaddress::MultiAddress<AccountId, AccountIndex>;
 /// Block header type as expected by this runtime.
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 /// Block type as expected by this runtime.
@@ -367,6 +780,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllModules,
+	migrations::NicksToIdentity,
 >;
 
 impl_runtime_apis! {
@@ -493,23 +907,33 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: fg_primitives::EquivocationProof<
+			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: fg_primitives::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
+		}
+	}
+
+	impl pallet_poe_runtime_api::PoeApi<Block> for Runtime {
+		fn verify_merkle_leaf(root: [u8; 32], leaf: [u8; 32], index: u32, proof: Vec<[u8; 32]>) -> bool {
+			PoeModule::verify_merkle_leaf(root, leaf, index, proof)
 		}
 	}
 
@@ -572,21 +996,27 @@ impl_runtime_apis! {
 
 
 parameter_types! {
-	pub const NickReservationFee: u128 = 100;
-	pub const MinNickLength: usize = 8;
-	pub const MaxNickLength: usize = 32;
+	pub const BasicDeposit: Balance = 1 * DOLLARS;
+	pub const FieldDeposit: Balance = 25 * CENTS;
+	pub const SubAccountDeposit: Balance = 20 * CENTS;
+	pub const MaxSubAccounts: u32 = 100;
+	pub const MaxAdditionalFields: u32 = 100;
+	pub const MaxRegistrars: u32 = 20;
 }
 
-impl pallet_nicks::Trait for Runtime {
-	type ReservationFee = NickReservationFee;
-	type MinLength = MinNickLength;
-	type MaxLength = MaxNickLength;
-	
-	type Currency = pallet_balances::Module<Runtime>;
-	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
-
-	type Slashed = ();
+impl pallet_identity::Trait for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type BasicDeposit = BasicDeposit;
+	type FieldDeposit = FieldDeposit;
+	type SubAccountDeposit = SubAccountDeposit;
+	type MaxSubAccounts = MaxSubAccounts;
+	type MaxAdditionalFields = MaxAdditionalFields;
+	type MaxRegistrars = MaxRegistrars;
+	type Slashed = Treasury;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type RegistrarOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = ();
 }
 
 parameter_types! {
@@ -600,7 +1030,369 @@ impl pallet_scheduler::Trait for Runtime {
 	type PalletsOrigin = OriginCaller;
 	type Call = Call;
 	type MaximumWeight = MaximumSchedulerWeight;
-	type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+	type ScheduleOrigin = EnsureRootOrHalfCouncil;
 	type MaxScheduledPerBlock = MaxScheduledPerBlock;
 	type WeightInfo = ();
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod call_filter_tests {
+	use super::*;
+	use frame_support::{assert_noop, assert_ok, dispatch::DispatchError};
+	use sp_runtime::traits::Dispatchable;
+
+	fn alice() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn dispatch(call: Call) -> Result<(), DispatchError> {
+		call.dispatch(Origin::signed(alice())).map(|_| ()).map_err(|e| e.error)
+	}
+
+	fn create_kitty() -> Call {
+		Call::KittiesModule(pallet_kitties::Call::create_kitty())
+	}
+
+	fn buy_kitty() -> Call {
+		Call::KittiesModule(pallet_kitties::Call::buy_kitty(1))
+	}
+
+	#[test]
+	fn paused_call_is_rejected_during_dispatch() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CircuitBreaker::pause_call(
+				Origin::root(),
+				b"KittiesModule".to_vec(),
+				b"buy_kitty".to_vec(),
+			));
+			assert_eq!(
+				System::events().pop().unwrap().event,
+				Event::pallet_circuit_breaker(pallet_circuit_breaker::Event::CallPaused(
+					b"KittiesModule".to_vec(),
+					b"buy_kitty".to_vec(),
+				)),
+			);
+
+			assert_eq!(dispatch(buy_kitty()), Err(DispatchError::BadOrigin));
+			assert_ok!(dispatch(create_kitty()));
+
+			assert_ok!(CircuitBreaker::resume_call(
+				Origin::root(),
+				b"KittiesModule".to_vec(),
+				b"buy_kitty".to_vec(),
+			));
+
+			assert_ne!(dispatch(buy_kitty()), Err(DispatchError::BadOrigin));
+		});
+	}
+
+	#[test]
+	fn paused_pallet_is_rejected_during_dispatch() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CircuitBreaker::pause_pallet(Origin::root(), b"KittiesModule".to_vec()));
+
+			assert_eq!(dispatch(create_kitty()), Err(DispatchError::BadOrigin));
+			assert_eq!(dispatch(buy_kitty()), Err(DispatchError::BadOrigin));
+			assert_ok!(dispatch(Call::System(frame_system::Call::remark(vec![]))));
+		});
+	}
+
+	#[test]
+	fn paused_call_is_rejected_inside_batch() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CircuitBreaker::pause_pallet(Origin::root(), b"KittiesModule".to_vec()));
+
+			assert_ok!(dispatch(Call::Utility(pallet_utility::Call::batch(vec![create_kitty()]))));
+			assert_eq!(KittiesModule::next_kitty_id(), 0);
+		});
+	}
+
+	#[test]
+	fn governance_cannot_be_paused() {
+		new_test_ext().execute_with(|| {
+			for pallet in &["Council", "CouncilMembership", "Democracy", "RuntimeUpgrade"] {
+				assert_noop!(
+					CircuitBreaker::pause_pallet(Origin::root(), pallet.as_bytes().to_vec()),
+					pallet_circuit_breaker::Error::<Runtime>::Unpausable
+				);
+			}
+
+			assert_noop!(
+				CircuitBreaker::pause_call(Origin::root(), b"Council".to_vec(), b"vote".to_vec()),
+				pallet_circuit_breaker::Error::<Runtime>::Unpausable
+			);
+		});
+	}
+
+	#[test]
+	fn system_and_sudo_cannot_be_paused() {
+		new_test_ext().execute_with(|| {
+			for pallet in &["System", "Sudo"] {
+				assert_noop!(
+					CircuitBreaker::pause_pallet(Origin::root(), pallet.as_bytes().to_vec()),
+					pallet_circuit_breaker::Error::<Runtime>::Unpausable
+				);
+				assert_noop!(
+					CircuitBreaker::pause_call(Origin::root(), pallet.as_bytes().to_vec(), b"remark".to_vec()),
+					pallet_circuit_breaker::Error::<Runtime>::Unpausable
+				);
+			}
+
+			assert_ok!(dispatch(Call::System(frame_system::Call::remark(vec![]))));
+		});
+	}
+}
+
+#[cfg(test)]
+mod contract_rent_tests {
+	use super::*;
+	use frame_support::{assert_ok, StorageMap};
+	use pallet_contracts::{ContractAddressFor, ContractInfoOf};
+	use sp_runtime::traits::Hash as HashT;
+
+	const GAS_LIMIT: Weight = 10_000_000_000;
+	/// Just enough to pay rent for the fixture's storage for a few blocks.
+	const SMALL_ENDOWMENT: Balance = 1 * CENTS;
+	/// Enough for the fixture's storage to be free of rent.
+	const LARGE_ENDOWMENT: Balance = 100 * DOLLARS;
+
+	fn alice() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn charlie() -> AccountId {
+		AccountId::from([3; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(alice(), 1_000 * DOLLARS), (charlie(), 1 * DOLLARS)],
+		}.assimilate_storage(&mut t).unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Uploads the fixture and returns its code hash.
+	fn put_code() -> Hash {
+		let wasm = wat::parse_str(include_str!("../fixtures/rent.wat")).unwrap();
+		assert_ok!(Contracts::put_code(Origin::signed(alice()), wasm.clone()));
+		<Runtime as frame_system::Trait>::Hashing::hash(&wasm)
+	}
+
+	/// Instantiates the fixture, distinguishing instances by `salt`.
+	fn instantiate(code_hash: Hash, endowment: Balance, salt: u8) -> AccountId {
+		assert_ok!(Contracts::instantiate(Origin::signed(alice()), endowment, GAS_LIMIT, code_hash, vec![salt]));
+		<Runtime as pallet_contracts::Trait>::DetermineContractAddress::contract_address_for(
+			&code_hash,
+			&[salt],
+			&alice(),
+		)
+	}
+
+	fn is_alive(contract: &AccountId) -> bool {
+		ContractInfoOf::<Runtime>::get(contract).and_then(|info| info.get_alive()).is_some()
+	}
+
+	fn is_tombstone(contract: &AccountId) -> bool {
+		ContractInfoOf::<Runtime>::get(contract).and_then(|info| info.get_tombstone()).is_some()
+	}
+
+	#[test]
+	fn rent_is_paid_to_treasury_on_eviction() {
+		new_test_ext().execute_with(|| {
+			let code_hash = put_code();
+			let contract = instantiate(code_hash, SMALL_ENDOWMENT, 0);
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 0);
+
+			System::set_block_number(100);
+			assert_ok!(Contracts::claim_surcharge(Origin::signed(charlie()), contract.clone(), None));
+
+			let rent = SMALL_ENDOWMENT - Balances::free_balance(&contract);
+			assert!(rent > 0);
+			assert_eq!(Balances::free_balance(Treasury::account_id()), rent);
+		});
+	}
+
+	#[test]
+	fn claim_surcharge_evicts_and_rewards() {
+		new_test_ext().execute_with(|| {
+			let code_hash = put_code();
+			let contract = instantiate(code_hash, SMALL_ENDOWMENT, 0);
+
+			System::set_block_number(100);
+			assert_ok!(Contracts::claim_surcharge(Origin::signed(charlie()), contract.clone(), None));
+
+			assert!(is_tombstone(&contract));
+			assert_eq!(Balances::free_balance(charlie()), 1 * DOLLARS + SurchargeReward::get());
+		});
+	}
+
+	#[test]
+	fn claim_surcharge_on_funded_contract_pays_nothing() {
+		new_test_ext().execute_with(|| {
+			let code_hash = put_code();
+			let contract = instantiate(code_hash, LARGE_ENDOWMENT, 0);
+
+			System::set_block_number(100);
+			// The 2.0.1 surcharge path succeeds whether or not anything was evicted.
+			assert_ok!(Contracts::claim_surcharge(Origin::signed(charlie()), contract.clone(), None));
+
+			assert!(is_alive(&contract));
+			assert_eq!(Balances::free_balance(charlie()), 1 * DOLLARS);
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 0);
+		});
+	}
+
+	#[test]
+	fn tombstone_cannot_be_called() {
+		new_test_ext().execute_with(|| {
+			let code_hash = put_code();
+			let contract = instantiate(code_hash, SMALL_ENDOWMENT, 0);
+
+			System::set_block_number(100);
+			assert_ok!(Contracts::claim_surcharge(Origin::signed(charlie()), contract.clone(), None));
+
+			assert!(Contracts::call(Origin::signed(alice()), contract, 0, GAS_LIMIT, vec![]).is_err());
+		});
+	}
+
+	#[test]
+	fn restore_to_revives_tombstone() {
+		new_test_ext().execute_with(|| {
+			let code_hash = put_code();
+			let evicted = instantiate(code_hash, SMALL_ENDOWMENT, 0);
+			let restorer = instantiate(code_hash, LARGE_ENDOWMENT, 1);
+
+			System::set_block_number(100);
+			assert_ok!(Contracts::claim_surcharge(Origin::signed(charlie()), evicted.clone(), None));
+			assert!(is_tombstone(&evicted));
+
+			// The restorer has the same storage as the evicted contract, so it can take its place.
+			let input = (evicted.clone(), code_hash, Balance::max_value()).encode();
+			assert_ok!(Contracts::call(Origin::signed(alice()), restorer.clone(), 0, GAS_LIMIT, input));
+
+			assert!(is_alive(&evicted));
+			assert!(ContractInfoOf::<Runtime>::get(&restorer).is_none());
+			assert!(Balances::free_balance(&evicted) > LARGE_ENDOWMENT);
+		});
+	}
+}
+
+#[cfg(test)]
+mod validator_set_tests {
+	use super::*;
+	use frame_support::{assert_noop, assert_ok};
+	use sp_core::{ed25519, sr25519};
+
+	fn validator() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn keys() -> opaque::SessionKeys {
+		opaque::SessionKeys {
+			aura: sr25519::Public::from_raw([1; 32]).into(),
+			grandpa: ed25519::Public::from_raw([1; 32]).into(),
+		}
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap().into()
+	}
+
+	/// Also guards the `pallet_session::NextKeys` key that `has_session_keys` reads by name.
+	#[test]
+	fn validators_need_session_keys() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				ValidatorSet::add_validator(Origin::root(), validator()),
+				pallet_validator_set::Error::<Runtime>::ValidatorKeysMissing
+			);
+
+			assert_ok!(Session::set_keys(Origin::signed(validator()), keys(), vec![]));
+			assert!(ValidatorSet::has_session_keys(&validator()));
+
+			assert_ok!(ValidatorSet::add_validator(Origin::root(), validator()));
+		});
+	}
+}
+
+#[cfg(test)]
+mod governance_tests {
+	use super::*;
+	use frame_support::{assert_noop, assert_ok, traits::EnsureOrigin};
+	use sp_runtime::{DispatchError, traits::Hash};
+
+	fn council(yes: u32, members: u32) -> Origin {
+		pallet_collective::RawOrigin::<AccountId, CouncilCollective>::Members(yes, members).into()
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn root_or_half_council_accepts_root_and_a_council_majority() {
+		assert!(EnsureRootOrHalfCouncil::try_origin(Origin::root()).is_ok());
+		assert!(EnsureRootOrHalfCouncil::try_origin(council(2, 3)).is_ok());
+		assert!(EnsureRootOrHalfCouncil::try_origin(council(3, 3)).is_ok());
+	}
+
+	#[test]
+	fn root_or_half_council_rejects_everyone_else() {
+		assert!(EnsureRootOrHalfCouncil::try_origin(Origin::signed(AccountId::from([1; 32]))).is_err());
+		assert!(EnsureRootOrHalfCouncil::try_origin(Origin::none()).is_err());
+		assert!(EnsureRootOrHalfCouncil::try_origin(council(1, 2)).is_err());
+		assert!(EnsureRootOrHalfCouncil::try_origin(council(1, 3)).is_err());
+		assert!(EnsureRootOrHalfCouncil::try_origin(
+			pallet_collective::RawOrigin::<AccountId, CouncilCollective>::Member(AccountId::from([1; 32])).into()
+		).is_err());
+	}
+
+	#[test]
+	fn council_majority_can_upgrade_the_runtime() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				RuntimeUpgrade::set_code(council(1, 2), vec![1, 2, 3]),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				RuntimeUpgrade::set_code(Origin::signed(AccountId::from([1; 32])), vec![1, 2, 3]),
+				DispatchError::BadOrigin
+			);
+
+			// Getting past `ensure_root`, `frame_system` rejects the code itself.
+			assert_noop!(
+				RuntimeUpgrade::set_code(council(2, 3), vec![1, 2, 3]),
+				frame_system::Error::<Runtime>::FailedToExtractRuntimeVersion
+			);
+		});
+	}
+
+	#[test]
+	fn council_majority_can_fast_track_a_runtime_upgrade_referendum() {
+		new_test_ext().execute_with(|| {
+			let proposal_hash = BlakeTwo256::hash_of(&Call::System(frame_system::Call::set_code(vec![])));
+
+			assert_noop!(
+				Democracy::external_propose_majority(council(1, 2), proposal_hash),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Democracy::external_propose_majority(council(2, 3), proposal_hash));
+			assert_ok!(Democracy::fast_track(council(2, 3), proposal_hash, FastTrackVotingPeriod::get(), 0));
+
+			assert_eq!(Democracy::referendum_count(), 1);
+		});
+	}
+}
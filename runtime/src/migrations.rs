@@ -0,0 +1,149 @@
+//! Storage migrations run by `Executive` on runtime upgrade.
+
+use frame_support::{
+	StorageMap, Twox64Concat,
+	storage::migration::{get_storage_value, put_storage_value, storage_key_iter},
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use pallet_identity::{Data, IdentityInfo, IdentityOf, Registration};
+use sp_std::prelude::*;
+use crate::{AccountId, Balance, Runtime};
+
+/// Replaces each nickname of the removed `Nicks` pallet with an identity whose `display` field
+/// is the nickname.
+///
+/// Nicknames are at most 32 bytes long, so they always fit in `Data::Raw`. The nickname's
+/// deposit stays reserved and becomes the identity's deposit, so it is returned when the
+/// identity is cleared. `Identity` is only added by the upgrade that runs this migration, so no
+/// account has an identity yet.
+///
+/// `Executive` runs this on every runtime upgrade, so a `Migrations NicksToIdentity` flag records
+/// that it is done and later upgrades skip the storage scan.
+pub struct NicksToIdentity;
+
+impl OnRuntimeUpgrade for NicksToIdentity {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = <Runtime as frame_system::Trait>::DbWeight::get();
+		if get_storage_value::<bool>(b"Migrations", b"NicksToIdentity", &[]).unwrap_or(false) {
+			return db_weight.reads(1);
+		}
+
+		let mut migrated: Weight = 0;
+
+		for (who, (nick, deposit)) in
+			storage_key_iter::<AccountId, (Vec<u8>, Balance), Twox64Concat>(b"Nicks", b"NameOf").drain()
+		{
+			IdentityOf::<Runtime>::insert(&who, Registration {
+				judgements: Vec::new(),
+				deposit,
+				info: IdentityInfo { display: Data::Raw(nick), ..Default::default() },
+			});
+			migrated += 1;
+		}
+		put_storage_value(b"Migrations", b"NicksToIdentity", &[], true);
+
+		db_weight.reads_writes(migrated + 2, 2 * migrated + 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use frame_support::{assert_ok, StorageHasher, traits::ReservableCurrency};
+	use crate::{Balances, DOLLARS, Identity, Origin};
+
+	fn alice() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::from([2; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(alice(), 10 * DOLLARS), (bob(), 10 * DOLLARS)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn nick_key(who: &AccountId) -> Vec<u8> {
+		Twox64Concat::hash(&who.encode())
+	}
+
+	/// Sets a nickname the way `pallet_nicks::set_name` did.
+	fn set_nick(who: &AccountId, nick: &[u8], deposit: Balance) {
+		Balances::reserve(who, deposit).unwrap();
+		put_storage_value(b"Nicks", b"NameOf", &nick_key(who), (nick.to_vec(), deposit));
+	}
+
+	#[test]
+	fn nicks_become_identity_display_names() {
+		new_test_ext().execute_with(|| {
+			set_nick(&alice(), b"alice-the-great", 100);
+
+			NicksToIdentity::on_runtime_upgrade();
+
+			let identity = Identity::identity(alice()).unwrap();
+			assert_eq!(identity.info.display, Data::Raw(b"alice-the-great".to_vec()));
+			assert_eq!(identity.deposit, 100);
+			assert!(identity.judgements.is_empty());
+			assert_eq!(Balances::reserved_balance(alice()), 100);
+
+			let nick = get_storage_value::<(Vec<u8>, Balance)>(b"Nicks", b"NameOf", &nick_key(&alice()));
+			assert_eq!(nick, None);
+		});
+	}
+
+	#[test]
+	fn migrated_deposit_is_returned_on_clear() {
+		new_test_ext().execute_with(|| {
+			set_nick(&alice(), b"alice-the-great", 100);
+
+			NicksToIdentity::on_runtime_upgrade();
+			assert_ok!(Identity::clear_identity(Origin::signed(alice())));
+
+			assert_eq!(Balances::reserved_balance(alice()), 0);
+			assert_eq!(Balances::free_balance(alice()), 10 * DOLLARS);
+		});
+	}
+
+	#[test]
+	fn accounts_without_nick_are_untouched() {
+		new_test_ext().execute_with(|| {
+			set_nick(&alice(), b"alice-the-great", 100);
+
+			NicksToIdentity::on_runtime_upgrade();
+
+			assert!(Identity::identity(bob()).is_none());
+		});
+	}
+
+	#[test]
+	fn migration_runs_once() {
+		new_test_ext().execute_with(|| {
+			set_nick(&alice(), b"alice-the-great", 100);
+
+			NicksToIdentity::on_runtime_upgrade();
+			NicksToIdentity::on_runtime_upgrade();
+
+			assert_eq!(Identity::identity(alice()).unwrap().deposit, 100);
+		});
+	}
+
+	#[test]
+	fn later_upgrades_skip_the_migration() {
+		new_test_ext().execute_with(|| {
+			NicksToIdentity::on_runtime_upgrade();
+			set_nick(&bob(), b"bob", 100);
+
+			NicksToIdentity::on_runtime_upgrade();
+
+			assert!(Identity::identity(bob()).is_none());
+			assert!(get_storage_value::<(Vec<u8>, Balance)>(b"Nicks", b"NameOf", &nick_key(&bob())).is_some());
+		});
+	}
+}
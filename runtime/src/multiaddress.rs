@@ -68,19 +68,45 @@ impl<AccountId: Default, AccountIndex> Default for MultiAddress<AccountId, Accou
 	}
 }
 
+/// Resolves the `MultiAddress` variants that do not carry an `AccountId` directly.
+pub trait AddressResolver<AccountId, AccountIndex> {
+	/// Returns the account that `index` is assigned to, if any.
+	fn resolve_index(index: AccountIndex) -> Option<AccountId>;
+
+	/// Returns the account linked to the 20-byte (Ethereum-style) `address`, if any.
+	fn resolve_address20(address: [u8; 20]) -> Option<AccountId>;
+}
+
+impl<AccountId, AccountIndex> AddressResolver<AccountId, AccountIndex> for () {
+	fn resolve_index(_: AccountIndex) -> Option<AccountId> {
+		None
+	}
+
+	fn resolve_address20(_: [u8; 20]) -> Option<AccountId> {
+		None
+	}
+}
+
 /// A lookup implementation returning the `AccountId` from a `MultiAddress`.
-pub struct AccountIdLookup<AccountId, AccountIndex>(PhantomData<(AccountId, AccountIndex)>);
-impl<AccountId, AccountIndex> StaticLookup for AccountIdLookup<AccountId, AccountIndex>
+///
+/// `MultiAddress::Id` is returned as is; other variants are resolved through `Resolver`.
+pub struct AccountIdLookup<AccountId, AccountIndex, Resolver = ()>(
+	PhantomData<(AccountId, AccountIndex, Resolver)>
+);
+impl<AccountId, AccountIndex, Resolver> StaticLookup for AccountIdLookup<AccountId, AccountIndex, Resolver>
 where
 	AccountId: Codec + Clone + PartialEq + Debug,
 	AccountIndex: Codec + Clone + PartialEq + Debug,
 	MultiAddress<AccountId, AccountIndex>: Codec,
+	Resolver: AddressResolver<AccountId, AccountIndex>,
 {
 	type Source = MultiAddress<AccountId, AccountIndex>;
 	type Target = AccountId;
 	fn lookup(x: Self::Source) -> Result<Self::Target, LookupError> {
 		match x {
 			MultiAddress::Id(i) => Ok(i),
+			MultiAddress::Index(i) => Resolver::resolve_index(i).ok_or(LookupError),
+			MultiAddress::Address20(a) => Resolver::resolve_address20(a).ok_or(LookupError),
 			_ => Err(LookupError),
 		}
 	}
@@ -88,3 +114,193 @@ where
 		MultiAddress::Id(x)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct TestResolver;
+	impl AddressResolver<u64, u32> for TestResolver {
+		fn resolve_index(index: u32) -> Option<u64> {
+			match index {
+				7 => Some(42),
+				_ => None,
+			}
+		}
+
+		fn resolve_address20(address: [u8; 20]) -> Option<u64> {
+			match address {
+				[2; 20] => Some(43),
+				_ => None,
+			}
+		}
+	}
+
+	type Lookup = AccountIdLookup<u64, u32, TestResolver>;
+
+	#[test]
+	fn lookup_returns_id_as_is() {
+		assert_eq!(Lookup::lookup(MultiAddress::Id(5)), Ok(5));
+	}
+
+	#[test]
+	fn lookup_resolves_known_index() {
+		assert_eq!(Lookup::lookup(MultiAddress::Index(7)), Ok(42));
+	}
+
+	#[test]
+	fn lookup_rejects_unknown_index() {
+		assert_eq!(Lookup::lookup(MultiAddress::Index(8)), Err(LookupError));
+		assert_eq!(AccountIdLookup::<u64, u32>::lookup(MultiAddress::Index(7)), Err(LookupError));
+	}
+
+	#[test]
+	fn lookup_rejects_raw() {
+		assert_eq!(Lookup::lookup(MultiAddress::Raw(vec![1, 2, 3])), Err(LookupError));
+	}
+
+	#[test]
+	fn lookup_rejects_address32() {
+		assert_eq!(Lookup::lookup(MultiAddress::Address32([1; 32])), Err(LookupError));
+	}
+
+	#[test]
+	fn lookup_resolves_linked_address20() {
+		assert_eq!(Lookup::lookup(MultiAddress::Address20([2; 20])), Ok(43));
+	}
+
+	#[test]
+	fn lookup_rejects_unlinked_address20() {
+		assert_eq!(Lookup::lookup(MultiAddress::Address20([1; 20])), Err(LookupError));
+		assert_eq!(AccountIdLookup::<u64, u32>::lookup(MultiAddress::Address20([2; 20])), Err(LookupError));
+	}
+
+	#[test]
+	fn unlookup_produces_id() {
+		assert_eq!(Lookup::unlookup(5), MultiAddress::Id(5));
+	}
+
+	mod scale {
+		use super::*;
+		use codec::Compact;
+		use hex_literal::hex;
+		use proptest::prelude::*;
+		use sp_core::crypto::AccountId32;
+
+		/// The address type used by the runtime.
+		type Address = MultiAddress<AccountId32, u32>;
+
+		fn address() -> impl Strategy<Value = Address> {
+			prop_oneof![
+				any::<[u8; 32]>().prop_map(|a| MultiAddress::Id(AccountId32::from(a))),
+				any::<u32>().prop_map(MultiAddress::Index),
+				any::<Vec<u8>>().prop_map(MultiAddress::Raw),
+				any::<[u8; 32]>().prop_map(MultiAddress::Address32),
+				any::<[u8; 20]>().prop_map(MultiAddress::Address20),
+			]
+		}
+
+		proptest! {
+			#[test]
+			fn round_trips(address in address()) {
+				let encoded = address.encode();
+				prop_assert_eq!(Address::decode(&mut &encoded[..]), Ok(address));
+			}
+
+			#[test]
+			fn index_is_compact_encoded(index in any::<u32>()) {
+				let mut expected = vec![1];
+				expected.extend(Compact(index).encode());
+				prop_assert_eq!(Address::Index(index).encode(), expected);
+			}
+
+			#[test]
+			fn rejects_unknown_variants(variant in 5u8.., rest in any::<Vec<u8>>()) {
+				let mut encoded = vec![variant];
+				encoded.extend(rest);
+				prop_assert!(Address::decode(&mut &encoded[..]).is_err());
+			}
+		}
+
+		// Golden vectors matching the encoding of upstream `sp_runtime::MultiAddress`.
+
+		#[test]
+		fn encodes_id() {
+			assert_eq!(
+				Address::Id(AccountId32::from([0x11; 32])).encode(),
+				hex!("00 1111111111111111111111111111111111111111111111111111111111111111").to_vec()
+			);
+		}
+
+		#[test]
+		fn encodes_index() {
+			assert_eq!(Address::Index(0).encode(), hex!("01 00").to_vec());
+			assert_eq!(Address::Index(63).encode(), hex!("01 fc").to_vec());
+			assert_eq!(Address::Index(64).encode(), hex!("01 0101").to_vec());
+			assert_eq!(Address::Index(16_384).encode(), hex!("01 02000100").to_vec());
+			assert_eq!(Address::Index(u32::max_value()).encode(), hex!("01 03ffffffff").to_vec());
+		}
+
+		#[test]
+		fn encodes_raw() {
+			assert_eq!(Address::Raw(vec![]).encode(), hex!("02 00").to_vec());
+			assert_eq!(Address::Raw(vec![0xaa, 0xbb, 0xcc]).encode(), hex!("02 0c aabbcc").to_vec());
+		}
+
+		#[test]
+		fn encodes_address32() {
+			assert_eq!(
+				Address::Address32([0x22; 32]).encode(),
+				hex!("03 2222222222222222222222222222222222222222222222222222222222222222").to_vec()
+			);
+		}
+
+		#[test]
+		fn encodes_address20() {
+			assert_eq!(
+				Address::Address20([0x33; 20]).encode(),
+				hex!("04 3333333333333333333333333333333333333333").to_vec()
+			);
+		}
+
+		#[test]
+		fn default_is_zero_id() {
+			assert_eq!(Address::default(), Address::Id(AccountId32::from([0; 32])));
+			assert_eq!(Address::default().encode(), [&[0u8][..], &[0; 32][..]].concat());
+		}
+	}
+
+	mod display {
+		use super::*;
+
+		type Address = MultiAddress<u64, u32>;
+
+		#[test]
+		fn formats_id_and_index_with_debug() {
+			assert_eq!(Address::Id(5).to_string(), "Id(5)");
+			assert_eq!(Address::Index(7).to_string(), "Index(7)");
+		}
+
+		#[test]
+		fn formats_raw_as_hex() {
+			assert_eq!(Address::Raw(vec![0xde, 0xad, 0xbe, 0xef]).to_string(), "MultiAddress::Raw(deadbeef)");
+			assert_eq!(Address::Raw(vec![]).to_string(), "MultiAddress::Raw()");
+		}
+
+		#[test]
+		fn formats_address32_as_hex() {
+			assert_eq!(
+				Address::Address32([0xab; 32]).to_string(),
+				format!("MultiAddress::Address32({})", "ab".repeat(32))
+			);
+		}
+
+		#[test]
+		fn formats_address20_as_hex() {
+			assert_eq!(
+				Address::Address20([0x01; 20]).to_string(),
+				format!("MultiAddress::Address20({})", "01".repeat(20))
+			);
+		}
+	}
+}